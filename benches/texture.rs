@@ -0,0 +1,99 @@
+// Compara cómo se leen las tiras de pared: el arreglo anidado `[x][y]` que usaba
+// `Texture` antes, un texel a la vez sobre el arreglo plano y `sample_column`
+// (con y sin filtro bilineal).
+//
+//     cargo bench --bench texture
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use graficas_proy1::texture::{Texture, TextureFilter};
+
+const STRIP_HEIGHT: usize = 600; // alto de la pared en pantalla, en píxeles
+const COLUMNS: usize = 256;      // tiras por iteración
+
+fn pattern(x: u32, y: u32) -> u32 {
+    0xFF000000 | (x.wrapping_mul(2654435761) ^ y.wrapping_mul(40503)) & 0xFFFFFF
+}
+
+// Almacenamiento anterior: un Vec por columna
+struct NestedTexture {
+    width: u32,
+    height: u32,
+    color_array: Vec<Vec<u32>>,
+}
+
+impl NestedTexture {
+    fn new(width: u32, height: u32) -> Self {
+        let color_array = (0..width).map(|x| (0..height).map(|y| pattern(x, y)).collect()).collect();
+        NestedTexture { width, height, color_array }
+    }
+
+    fn get_pixel_color(&self, x: u32, y: u32) -> u32 {
+        if x >= self.width || y >= self.height {
+            0xFF00FF
+        } else {
+            self.color_array[x as usize][y as usize]
+        }
+    }
+}
+
+fn bench_strips(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wall_strips");
+    let mut strip = vec![0; STRIP_HEIGHT];
+    let v_step = 1.0 / STRIP_HEIGHT as f32;
+
+    for size in [500, 512] {
+        let nested = NestedTexture::new(size, size);
+        let texture = Texture::from_fn(size, size, pattern);
+
+        // Como lo hacía render3d: coordenadas de 0 a 128 escaladas en cada texel
+        group.bench_function(format!("nested_per_texel_{size}"), |b| {
+            b.iter(|| {
+                for column in 0..COLUMNS {
+                    let tx = (column * 128 / COLUMNS) as u32;
+                    for (y, texel) in strip.iter_mut().enumerate() {
+                        let ty = (y as f32 / STRIP_HEIGHT as f32 * 128.0) as u32;
+                        *texel = nested.get_pixel_color(tx * nested.width / 128, ty * nested.height / 128);
+                    }
+                    black_box(&strip);
+                }
+            })
+        });
+
+        group.bench_function(format!("flat_per_texel_{size}"), |b| {
+            b.iter(|| {
+                for column in 0..COLUMNS {
+                    let tx = (column * 128 / COLUMNS) as u32;
+                    for (y, texel) in strip.iter_mut().enumerate() {
+                        let ty = (y as f32 / STRIP_HEIGHT as f32 * 128.0) as u32;
+                        *texel = texture.get_pixel_argb(tx * texture.width / 128, ty * texture.height / 128);
+                    }
+                    black_box(&strip);
+                }
+            })
+        });
+
+        group.bench_function(format!("sample_column_{size}"), |b| {
+            b.iter(|| {
+                for column in 0..COLUMNS {
+                    let u = column as f32 / COLUMNS as f32;
+                    texture.sample_column(u, 0.0, v_step, TextureFilter::Nearest, &mut strip);
+                    black_box(&strip);
+                }
+            })
+        });
+
+        group.bench_function(format!("sample_column_bilinear_{size}"), |b| {
+            b.iter(|| {
+                for column in 0..COLUMNS {
+                    let u = column as f32 / COLUMNS as f32;
+                    texture.sample_column(u, 0.0, v_step, TextureFilter::Bilinear, &mut strip);
+                    black_box(&strip);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_strips);
+criterion_main!(benches);
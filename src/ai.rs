@@ -0,0 +1,283 @@
+// ai.rs
+
+use nalgebra_glm::Vec2;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::f32::consts::PI;
+
+use crate::combat::Projectile;
+use crate::door::Doors;
+use crate::enemy::{Enemy, EnemyKind, ENEMY_RADIUS};
+use crate::maze::Level;
+use crate::player::{is_solid, move_and_slide, Player};
+use crate::raycast::trace_ray;
+use crate::theme::Theme;
+
+const SIGHT_RANGE: f32 = 8.0;          // Alcance de la vista, en bloques
+const SIGHT_FOV: f32 = PI * 2.0 / 3.0; // Campo de visión de los enemigos
+const REACTION_TIME: f32 = 0.6;        // Segundos en alerta antes de perseguir
+const SEARCH_TIME: f32 = 5.0;          // Segundos buscando antes de rendirse
+const REPATH_TIME: f32 = 0.5;          // Cada cuánto se recalcula el camino al perseguir
+const IDLE_TURN_SPEED: f32 = 0.5;      // Radianes por segundo al mirar alrededor
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AiState {
+    Idle,                  // quieto, mirando alrededor
+    Patrol,                // recorre sus puntos de patrulla
+    Alert { timer: f32 },  // vio al jugador y está reaccionando
+    Chase,                 // persigue al jugador
+    Search { timer: f32 }, // perdió al jugador y va a donde lo vio por última vez
+}
+
+// Estado de la IA de un enemigo
+#[derive(Clone)]
+pub struct Brain {
+    pub state: AiState,
+    pub waypoints: Vec<(usize, usize)>, // celdas (columna, fila) de la patrulla
+    next_waypoint: usize,
+    path: Vec<(usize, usize)>,          // camino actual, con la siguiente celda al final
+    repath_timer: f32,
+    attack_timer: f32,                  // segundos para el siguiente disparo
+}
+
+impl Brain {
+    pub fn new(waypoints: Vec<(usize, usize)>) -> Self {
+        let state = if waypoints.is_empty() { AiState::Idle } else { AiState::Patrol };
+        Brain {
+            state,
+            waypoints,
+            next_waypoint: 0,
+            path: Vec::new(),
+            repath_timer: 0.0,
+            attack_timer: 0.0,
+        }
+    }
+
+    // Estado al que vuelve el enemigo cuando deja de buscar
+    fn resting_state(&self) -> AiState {
+        if self.waypoints.is_empty() { AiState::Idle } else { AiState::Patrol }
+    }
+}
+
+fn speed(kind: EnemyKind) -> f32 {
+    match kind {
+        EnemyKind::Trooper => 70.0,
+        EnemyKind::Officer => 85.0,
+        EnemyKind::Droid => 50.0,
+    }
+}
+
+fn cell_of(pos: Vec2, block_size: usize) -> (usize, usize) {
+    let block = block_size as f32;
+    ((pos.x / block).max(0.0) as usize, (pos.y / block).max(0.0) as usize)
+}
+
+fn cell_center(cell: (usize, usize), block_size: usize) -> Vec2 {
+    Vec2::new(cell.0 as f32 + 0.5, cell.1 as f32 + 0.5) * block_size as f32
+}
+
+// Diferencia entre dos ángulos, entre -π y π
+fn angle_difference(a: f32, b: f32) -> f32 {
+    (a - b + PI).rem_euclid(2.0 * PI) - PI
+}
+
+// Verdadero si nada sólido se interpone entre `from` y `to` (las barreras cuentan como cerradas)
+pub fn has_line_of_sight(from: Vec2, to: Vec2, maze: &[Vec<char>], doors: &Doors, block_size: usize) -> bool {
+    let offset = to - from;
+    let a = offset.y.atan2(offset.x);
+    trace_ray(maze, doors, from, &[], a, block_size).distance >= offset.magnitude()
+}
+
+// El enemigo ve al jugador si está cerca, dentro de su campo de visión y sin paredes en medio
+fn can_see(enemy: &Enemy, player: &Player, maze: &[Vec<char>], doors: &Doors, block_size: usize) -> bool {
+    let offset = player.pos - enemy.pos;
+    if offset.magnitude() > SIGHT_RANGE * block_size as f32 {
+        return false;
+    }
+    let angle = offset.y.atan2(offset.x);
+    angle_difference(angle, enemy.facing).abs() <= SIGHT_FOV / 2.0
+        && has_line_of_sight(enemy.pos, player.pos, maze, doors, block_size)
+}
+
+// A* sobre las celdas del laberinto con vecinos en 4 direcciones.
+// Devuelve el camino sin la celda inicial, con la siguiente celda al final.
+pub fn find_path(
+    start: (usize, usize),
+    goal: (usize, usize),
+    maze: &[Vec<char>],
+    theme: &Theme,
+    doors: &Doors,
+) -> Option<Vec<(usize, usize)>> {
+    let heuristic = |(col, row): (usize, usize)| col.abs_diff(goal.0) + row.abs_diff(goal.1);
+
+    let mut open = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    let mut cost: HashMap<(usize, usize), usize> = HashMap::from([(start, 0)]);
+
+    while let Some(Reverse((_, current_cost, current))) = open.pop() {
+        if current == goal && current == start {
+            return Some(Vec::new());
+        }
+        if current == goal {
+            let mut path = vec![current];
+            let mut cell = current;
+            while let Some(&previous) = came_from.get(&cell) {
+                if previous == start {
+                    break;
+                }
+                path.push(previous);
+                cell = previous;
+            }
+            return Some(path);
+        }
+        if current_cost > cost[&current] {
+            continue; // Entrada vieja de la cola
+        }
+
+        let (col, row) = (current.0 as isize, current.1 as isize);
+        for (n_col, n_row) in [(col - 1, row), (col + 1, row), (col, row - 1), (col, row + 1)] {
+            if is_solid(n_col, n_row, maze, theme, doors, &[]) {
+                continue;
+            }
+            let neighbor = (n_col as usize, n_row as usize);
+            let new_cost = current_cost + 1;
+            if cost.get(&neighbor).is_none_or(|&old| new_cost < old) {
+                cost.insert(neighbor, new_cost);
+                came_from.insert(neighbor, current);
+                open.push(Reverse((new_cost + heuristic(neighbor), new_cost, neighbor)));
+            }
+        }
+    }
+    None
+}
+
+// Avanza por el camino calculado; devuelve verdadero al llegar al final
+fn follow_path(enemy: &mut Enemy, speed: f32, level: &Level, doors: &Doors, block_size: usize, dt: f32) -> bool {
+    let Some(&next) = enemy.brain.path.last() else {
+        return true;
+    };
+
+    let target = cell_center(next, block_size);
+    let offset = target - enemy.pos;
+    let step = speed * dt;
+    if offset.magnitude() <= step {
+        enemy.pos = move_and_slide(enemy.pos, offset, ENEMY_RADIUS, level, doors, &[], block_size);
+        enemy.brain.path.pop();
+    } else {
+        let delta = offset.normalize() * step;
+        enemy.pos = move_and_slide(enemy.pos, delta, ENEMY_RADIUS, level, doors, &[], block_size);
+    }
+    enemy.facing = offset.y.atan2(offset.x);
+    enemy.brain.path.is_empty()
+}
+
+fn set_path(enemy: &mut Enemy, goal: (usize, usize), maze: &[Vec<char>], theme: &Theme, doors: &Doors, block_size: usize) {
+    let start = cell_of(enemy.pos, block_size);
+    enemy.brain.path = find_path(start, goal, maze, theme, doors).unwrap_or_default();
+}
+
+// Dispara al jugador cuando se cumple el tiempo entre disparos (solo enemigos con ataque a distancia)
+fn attack(enemy: &mut Enemy, player: &Player, projectiles: &mut Vec<Projectile>, dt: f32) {
+    let Some((damage, interval)) = enemy.kind.ranged_attack() else {
+        return;
+    };
+    enemy.brain.attack_timer -= dt;
+    if enemy.brain.attack_timer <= 0.0 {
+        projectiles.push(Projectile::aimed(enemy.pos, player.pos, damage));
+        enemy.brain.attack_timer = interval;
+    }
+}
+
+// Avanza la IA de un enemigo `dt` segundos; sus disparos se agregan a `projectiles`
+pub fn update_enemy(
+    enemy: &mut Enemy,
+    player: &Player,
+    level: &Level,
+    doors: &Doors,
+    projectiles: &mut Vec<Projectile>,
+    block_size: usize,
+    dt: f32,
+) {
+    enemy.animation_time += dt;
+    if !enemy.is_alive() {
+        return;
+    }
+    let (maze, theme) = (&level.maze, &level.theme);
+    let start = enemy.pos;
+
+    let sees_player = can_see(enemy, player, maze, doors, block_size);
+    let speed = speed(enemy.kind);
+
+    enemy.brain.state = match enemy.brain.state {
+        AiState::Idle | AiState::Patrol if sees_player => AiState::Alert { timer: REACTION_TIME },
+        AiState::Idle => {
+            enemy.facing = (enemy.facing + IDLE_TURN_SPEED * dt).rem_euclid(2.0 * PI);
+            AiState::Idle
+        }
+        AiState::Patrol => {
+            if enemy.brain.path.is_empty() {
+                let waypoint = enemy.brain.waypoints[enemy.brain.next_waypoint];
+                set_path(enemy, waypoint, maze, theme, doors, block_size);
+                enemy.brain.next_waypoint = (enemy.brain.next_waypoint + 1) % enemy.brain.waypoints.len();
+            }
+            follow_path(enemy, speed * 0.5, level, doors, block_size, dt);
+            AiState::Patrol
+        }
+        AiState::Alert { timer } => {
+            let offset = player.pos - enemy.pos;
+            enemy.facing = offset.y.atan2(offset.x);
+            if !sees_player {
+                set_path(enemy, cell_of(player.pos, block_size), maze, theme, doors, block_size);
+                AiState::Search { timer: SEARCH_TIME }
+            } else if timer <= dt {
+                enemy.brain.repath_timer = 0.0;
+                enemy.brain.attack_timer = REACTION_TIME;
+                AiState::Chase
+            } else {
+                AiState::Alert { timer: timer - dt }
+            }
+        }
+        AiState::Chase => {
+            enemy.brain.repath_timer -= dt;
+            if enemy.brain.repath_timer <= 0.0 || enemy.brain.path.is_empty() {
+                set_path(enemy, cell_of(player.pos, block_size), maze, theme, doors, block_size);
+                enemy.brain.repath_timer = REPATH_TIME;
+            }
+            if enemy.brain.path.is_empty() && sees_player {
+                // En la misma celda que el jugador: ir directo hacia él hasta tocarlo
+                let offset = player.pos - enemy.pos;
+                let gap = offset.magnitude() - (ENEMY_RADIUS + player.radius) * 0.9;
+                if gap > 0.0 {
+                    let delta = offset.normalize() * (speed * dt).min(gap);
+                    enemy.pos = move_and_slide(enemy.pos, delta, ENEMY_RADIUS, level, doors, &[], block_size);
+                    enemy.facing = offset.y.atan2(offset.x);
+                }
+            } else {
+                follow_path(enemy, speed, level, doors, block_size, dt);
+            }
+            if sees_player {
+                attack(enemy, player, projectiles, dt);
+                AiState::Chase
+            } else {
+                set_path(enemy, cell_of(player.pos, block_size), maze, theme, doors, block_size);
+                AiState::Search { timer: SEARCH_TIME }
+            }
+        }
+        AiState::Search { timer } => {
+            if sees_player {
+                AiState::Chase
+            } else if timer <= dt {
+                enemy.brain.path.clear();
+                enemy.brain.resting_state()
+            } else {
+                if follow_path(enemy, speed * 0.75, level, doors, block_size, dt) {
+                    // Mira alrededor donde lo perdió
+                    enemy.facing = (enemy.facing + IDLE_TURN_SPEED * 2.0 * dt).rem_euclid(2.0 * PI);
+                }
+                AiState::Search { timer: timer - dt }
+            }
+        }
+    };
+
+    enemy.moving = (enemy.pos - start).magnitude() > 0.01;
+}
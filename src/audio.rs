@@ -4,26 +4,37 @@ use rodio::{Decoder, OutputStream, Sink, Source};
 use std::fs::File;
 use std::io::BufReader;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::error::GameError;
 
 pub struct AudioPlayer {
     sink: Arc<Mutex<Sink>>,
     _stream: OutputStream,
+    music_file: String,
+}
+
+fn open_source(music_file: &str) -> Result<Decoder<BufReader<File>>, GameError> {
+    let file = File::open(music_file)
+        .map_err(|source| GameError::Io { path: music_file.to_string(), source })?;
+    Decoder::new(BufReader::new(file))
+        .map_err(|err| GameError::Audio { path: music_file.to_string(), message: err.to_string() })
 }
 
 impl AudioPlayer {
-    pub fn new(music_file: &str) -> Self {
-        let (stream, stream_handle) = OutputStream::try_default().unwrap();
-        let sink = Sink::try_new(&stream_handle).unwrap();
+    pub fn try_new(music_file: &str) -> Result<Self, GameError> {
+        let audio_error = |message: String| GameError::Audio { path: music_file.to_string(), message };
+        let (stream, stream_handle) = OutputStream::try_default().map_err(|err| audio_error(err.to_string()))?;
+        let sink = Sink::try_new(&stream_handle).map_err(|err| audio_error(err.to_string()))?;
 
-        let file = BufReader::new(File::open(music_file).unwrap());
-        let source = Decoder::new(file).unwrap();
+        let source = open_source(music_file)?;
         sink.append(source);
         sink.set_volume(0.5);
 
-        AudioPlayer {
+        Ok(AudioPlayer {
             sink: Arc::new(Mutex::new(sink)),
             _stream: stream,
-        }
+            music_file: music_file.to_string(),
+        })
     }
 
     pub fn play(&self) {
@@ -37,10 +48,22 @@ impl AudioPlayer {
     pub fn play_loop(&self) {
         let sink_guard = self.sink.lock().unwrap();
         if sink_guard.empty() {
-            let file = BufReader::new(File::open("assets/death_star_alarm.mp3").unwrap());
-            let source = Decoder::new(file).unwrap();
-            sink_guard.append(source.repeat_infinite()); // Reproducir en bucle
+            match open_source(&self.music_file) {
+                Ok(source) => sink_guard.append(source.repeat_infinite()), // Reproducir en bucle
+                Err(err) => eprintln!("{}", err),
+            }
         }
         sink_guard.play();
     }
-}
\ No newline at end of file
+}
+
+// Reproduce un efecto de sonido y espera a que termine
+pub fn play_sound(path: &str, duration: Duration) {
+    match AudioPlayer::try_new(path) {
+        Ok(sound) => {
+            sound.play();
+            std::thread::sleep(duration);
+        }
+        Err(err) => eprintln!("{}", err),
+    }
+}
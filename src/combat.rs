@@ -0,0 +1,108 @@
+// combat.rs
+
+use nalgebra_glm::Vec2;
+
+use crate::door::Doors;
+use crate::enemy::{Enemy, ENEMY_RADIUS};
+use crate::player::{is_collision, Player};
+use crate::raycast::trace_ray;
+use crate::theme::Theme;
+
+const SHOT_DAMAGE: f32 = 34.0;
+const SHOT_COOLDOWN: f32 = 0.35;  // Segundos entre disparos del jugador
+const PROJECTILE_SPEED: f32 = 220.0; // Píxeles por segundo
+const PROJECTILE_RADIUS: f32 = 4.0;
+
+// Disparo enemigo que viaja por el laberinto hasta chocar con una pared o con el jugador
+#[derive(Clone)]
+pub struct Projectile {
+    pub pos: Vec2,
+    pub velocity: Vec2,
+    pub damage: f32,
+}
+
+impl Projectile {
+    // Disparo desde `from` apuntando a `target`
+    pub fn aimed(from: Vec2, target: Vec2, damage: f32) -> Self {
+        let dir = (target - from).normalize();
+        Projectile {
+            pos: from,
+            velocity: dir * PROJECTILE_SPEED,
+            damage,
+        }
+    }
+}
+
+// Dispara un rayo instantáneo en la dirección de la mirada. El rayo contra las paredes
+// hace de z-buffer: solo se puede herir a un enemigo más cercano que la pared.
+// Devuelve el id del enemigo herido.
+fn fire_hitscan(player: &Player, enemies: &mut [Enemy], maze: &[Vec<char>], doors: &Doors, block_size: usize) -> Option<usize> {
+    let dir = Vec2::new(player.a.cos(), player.a.sin());
+    let wall_distance = trace_ray(maze, doors, player.pos, &player.inventory, player.a, block_size).distance;
+
+    let target = enemies
+        .iter_mut()
+        .filter(|enemy| enemy.is_alive())
+        .filter_map(|enemy| {
+            // Distancia a lo largo del rayo y qué tan lejos del rayo pasa el enemigo
+            let offset = enemy.pos - player.pos;
+            let along = offset.dot(&dir);
+            let across = (offset - dir * along).magnitude();
+            (along > 0.0 && along < wall_distance && across < enemy.kind.hit_radius()).then_some((along, enemy))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let (_, enemy) = target?;
+    enemy.take_damage(SHOT_DAMAGE);
+    Some(enemy.id)
+}
+
+// Avanza el arma del jugador; dispara si `firing` y ya pasó el tiempo entre disparos
+pub fn update_weapon(
+    player: &mut Player,
+    firing: bool,
+    enemies: &mut [Enemy],
+    maze: &[Vec<char>],
+    doors: &Doors,
+    block_size: usize,
+    dt: f32,
+) -> Option<usize> {
+    player.weapon_cooldown = (player.weapon_cooldown - dt).max(0.0);
+    if !firing || player.weapon_cooldown > 0.0 || player.ammo == 0 {
+        return None;
+    }
+
+    player.ammo -= 1;
+    player.weapon_cooldown = SHOT_COOLDOWN;
+    fire_hitscan(player, enemies, maze, doors, block_size)
+}
+
+// Mueve los disparos enemigos y aplica su daño; los que chocan desaparecen
+pub fn update_projectiles(
+    projectiles: &mut Vec<Projectile>,
+    player: &mut Player,
+    maze: &[Vec<char>],
+    theme: &Theme,
+    doors: &Doors,
+    block_size: usize,
+    dt: f32,
+) {
+    projectiles.retain_mut(|projectile| {
+        projectile.pos += projectile.velocity * dt;
+
+        if (projectile.pos - player.pos).magnitude() < player.radius + PROJECTILE_RADIUS {
+            player.take_damage(projectile.damage);
+            return false;
+        }
+        !is_collision(projectile.pos.x, projectile.pos.y, maze, theme, doors, &[], block_size)
+    });
+}
+
+// Los enemigos vivos que tocan al jugador le hacen daño continuo
+pub fn apply_contact_damage(enemies: &[Enemy], player: &mut Player, dt: f32) {
+    for enemy in enemies.iter().filter(|enemy| enemy.is_alive()) {
+        if (enemy.pos - player.pos).magnitude() < ENEMY_RADIUS + player.radius {
+            player.take_damage(enemy.kind.contact_damage() * dt);
+        }
+    }
+}
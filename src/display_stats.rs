@@ -24,6 +24,11 @@ impl Timer {
         self.fps = 1.0 / self.delta_time.as_secs_f32();
     }
 
+    // Segundos transcurridos entre los dos últimos cuadros
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time.as_secs_f32()
+    }
+
     pub fn get_fps(&self) -> f32 {
         let fps = self.fps;
         fps
@@ -0,0 +1,106 @@
+// door.rs
+
+use std::collections::HashMap;
+
+const DOOR_SPEED: f32 = 1.2; // Fracción del bloque que se desliza la puerta por segundo
+
+// 'D' puerta horizontal, 'd' puerta vertical
+pub fn is_door(cell: char) -> bool {
+    cell == 'D' || cell == 'd'
+}
+
+pub struct Door {
+    pub open: f32, // 0.0 cerrada, 1.0 abierta
+    opening: bool,
+}
+
+pub struct Doors {
+    doors: HashMap<(usize, usize), Door>,
+}
+
+impl Doors {
+    pub fn from_maze(maze: &[Vec<char>]) -> Self {
+        let mut doors = HashMap::new();
+        for (row, line) in maze.iter().enumerate() {
+            for (col, &cell) in line.iter().enumerate() {
+                if is_door(cell) {
+                    doors.insert((col, row), Door { open: 0.0, opening: false });
+                }
+            }
+        }
+        Doors { doors }
+    }
+
+    // Qué tan abierta está la puerta en (col, row)
+    pub fn openness(&self, col: usize, row: usize) -> f32 {
+        self.doors.get(&(col, row)).map_or(0.0, |door| door.open)
+    }
+
+    // Una puerta solo deja pasar cuando está completamente abierta
+    pub fn is_passable(&self, col: usize, row: usize) -> bool {
+        self.openness(col, row) >= 1.0
+    }
+
+    // Abre la puerta si está cerrada (o cerrándose) y la cierra si está abierta
+    pub fn toggle(&mut self, col: usize, row: usize) {
+        if let Some(door) = self.doors.get_mut(&(col, row)) {
+            door.opening = !door.opening;
+        }
+    }
+
+    // Avanza la animación de todas las puertas `dt` segundos
+    pub fn update(&mut self, dt: f32) {
+        for door in self.doors.values_mut() {
+            if door.opening {
+                door.open = (door.open + DOOR_SPEED * dt).min(1.0);
+            } else {
+                door.open = (door.open - DOOR_SPEED * dt).max(0.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doors() -> Doors {
+        Doors::from_maze(&[vec!['#', 'D', '#'], vec!['#', 'd', '#']])
+    }
+
+    #[test]
+    fn doors_start_closed() {
+        let doors = doors();
+        assert_eq!(doors.openness(1, 0), 0.0);
+        assert!(!doors.is_passable(1, 0));
+        assert!(!doors.is_passable(1, 1));
+        // Las celdas sin puerta no cuentan como puertas abiertas
+        assert!(!doors.is_passable(0, 0));
+    }
+
+    #[test]
+    fn toggled_door_slides_open_and_only_then_lets_through() {
+        let mut doors = doors();
+        doors.toggle(1, 0);
+        doors.update(0.5);
+        assert!((doors.openness(1, 0) - 0.6).abs() < 1e-6);
+        assert!(!doors.is_passable(1, 0));
+        doors.update(0.5);
+        assert_eq!(doors.openness(1, 0), 1.0);
+        assert!(doors.is_passable(1, 0));
+        // La otra puerta no se movió
+        assert_eq!(doors.openness(1, 1), 0.0);
+    }
+
+    #[test]
+    fn toggling_while_opening_closes_it_again() {
+        let mut doors = doors();
+        doors.toggle(1, 1);
+        doors.update(0.5);
+        doors.toggle(1, 1);
+        doors.update(0.25);
+        assert!((doors.openness(1, 1) - 0.3).abs() < 1e-6);
+        doors.update(1.0);
+        assert_eq!(doors.openness(1, 1), 0.0);
+    }
+}
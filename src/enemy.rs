@@ -2,19 +2,160 @@
 
 use nalgebra_glm::Vec2;
 use once_cell::sync::Lazy;
-use std::sync::Arc;
 use crate::texture::Texture;
+use crate::game_loop::{lerp_angle, lerp_vec2};
+use crate::ai::Brain;
+use crate::sprite::{AnimationClip, ClipFrame, SpriteSheet};
 
-pub static ENEMY_TEXTURE: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/sprite1.png")));
+// Columnas: 8 direcciones de vista. Filas: 0 quieto, 1-2 caminando, 3-5 muriendo.
+pub static ENEMY_SHEET: Lazy<SpriteSheet> = Lazy::new(|| SpriteSheet::new(Texture::new("assets/enemy_sheet.png"), 8, 6));
+pub const ENEMY_DIRECTIONS: u32 = 8;
 
+pub const ENEMY_RADIUS: f32 = 14.0; // Radio de colisión de los enemigos (paredes, puertas y contacto)
+const BODY_WIDTH: f32 = 0.5;        // Fracción del ancho del sprite que ocupa el cuerpo
+
+const IDLE_CLIP: AnimationClip = AnimationClip {
+    frames: &[ClipFrame { row: 0, duration: 1.0 }],
+    looping: true,
+    directional: true,
+};
+const WALK_CLIP: AnimationClip = AnimationClip {
+    frames: &[
+        ClipFrame { row: 1, duration: 0.2 },
+        ClipFrame { row: 0, duration: 0.1 },
+        ClipFrame { row: 2, duration: 0.2 },
+        ClipFrame { row: 0, duration: 0.1 },
+    ],
+    looping: true,
+    directional: true,
+};
+const DEATH_CLIP: AnimationClip = AnimationClip {
+    frames: &[
+        ClipFrame { row: 3, duration: 0.15 },
+        ClipFrame { row: 4, duration: 0.15 },
+        ClipFrame { row: 5, duration: 1.0 },
+    ],
+    looping: false,
+    directional: false,
+};
+
+// Tipos de enemigo; cada uno se coloca en el laberinto con su propio carácter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnemyKind {
+    Trooper, // 'e'
+    Officer, // 'o'
+    Droid,   // 'x'
+}
+
+impl EnemyKind {
+    pub fn from_glyph(cell: char) -> Option<Self> {
+        match cell {
+            'e' => Some(EnemyKind::Trooper),
+            'o' => Some(EnemyKind::Officer),
+            'x' => Some(EnemyKind::Droid),
+            _ => None,
+        }
+    }
+
+    pub fn max_health(self) -> f32 {
+        match self {
+            EnemyKind::Trooper => 100.0,
+            EnemyKind::Officer => 60.0,
+            EnemyKind::Droid => 150.0,
+        }
+    }
+
+    // Tamaño del sprite (ancho, alto) en unidades del mundo
+    pub fn sprite_size(self) -> (f32, f32) {
+        match self {
+            EnemyKind::Trooper | EnemyKind::Officer => (70.0, 70.0),
+            EnemyKind::Droid => (50.0, 50.0),
+        }
+    }
+
+    // Distancia máxima entre un disparo y el centro del enemigo para que le pegue:
+    // la mitad del ancho del cuerpo dibujado
+    pub fn hit_radius(self) -> f32 {
+        self.sprite_size().0 * BODY_WIDTH / 2.0
+    }
+
+    // Daño por segundo mientras toca al jugador
+    pub fn contact_damage(self) -> f32 {
+        match self {
+            EnemyKind::Trooper => 20.0,
+            EnemyKind::Officer => 15.0,
+            EnemyKind::Droid => 35.0,
+        }
+    }
+
+    // Disparo a distancia como (daño, segundos entre disparos); los droides solo atacan de cerca
+    pub fn ranged_attack(self) -> Option<(f32, f32)> {
+        match self {
+            EnemyKind::Trooper => Some((10.0, 1.5)),
+            EnemyKind::Officer => Some((6.0, 0.9)),
+            EnemyKind::Droid => None,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Enemy {
+    pub id: usize,
+    pub kind: EnemyKind,
     pub pos: Vec2,
+    pub health: f32,
+    pub facing: f32, // ángulo hacia donde mira
+    pub brain: Brain,
+    pub moving: bool,         // si se movió en el último tick
+    pub animation_time: f32,  // segundos desde que empezó la animación actual
 }
 
 impl Enemy {
-    pub fn new(x: f32, y: f32) -> Self {
+    pub fn new(id: usize, kind: EnemyKind, pos: Vec2, facing: f32) -> Self {
+        Enemy {
+            id,
+            kind,
+            pos,
+            health: kind.max_health(),
+            facing,
+            brain: Brain::new(Vec::new()),
+            moving: false,
+            animation_time: 0.0,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.health > 0.0
+    }
+
+    // Resta vida; al morir empieza la animación de muerte
+    pub fn take_damage(&mut self, amount: f32) {
+        if !self.is_alive() {
+            return;
+        }
+        self.health = (self.health - amount).max(0.0);
+        if !self.is_alive() {
+            self.animation_time = 0.0;
+        }
+    }
+
+    // Animación según el estado: muriendo, caminando o quieto
+    pub fn clip(&self) -> &'static AnimationClip {
+        if !self.is_alive() {
+            &DEATH_CLIP
+        } else if self.moving {
+            &WALK_CLIP
+        } else {
+            &IDLE_CLIP
+        }
+    }
+
+    // Estado para dibujar entre el tick anterior (`previous`) y el actual
+    pub fn interpolated(&self, previous: &Enemy, alpha: f32) -> Enemy {
         Enemy {
-            pos: Vec2::new(x, y),
+            pos: lerp_vec2(previous.pos, self.pos, alpha),
+            facing: lerp_angle(previous.facing, self.facing, alpha),
+            ..self.clone()
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,34 @@
+// error.rs
+
+use std::fmt;
+use std::io;
+
+// Errores al cargar recursos del juego; siempre incluyen la ruta del archivo
+#[derive(Debug)]
+pub enum GameError {
+    Io { path: String, source: io::Error },
+    Image { path: String, source: image::ImageError },
+    Audio { path: String, message: String },
+    InvalidLevel { path: String, message: String },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::Io { path, source } => write!(f, "No se pudo leer '{}': {}", path, source),
+            GameError::Image { path, source } => write!(f, "No se pudo cargar la imagen '{}': {}", path, source),
+            GameError::Audio { path, message } => write!(f, "No se pudo reproducir '{}': {}", path, message),
+            GameError::InvalidLevel { path, message } => write!(f, "Nivel invalido '{}': {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::Io { source, .. } => Some(source),
+            GameError::Image { source, .. } => Some(source),
+            GameError::Audio { .. } | GameError::InvalidLevel { .. } => None,
+        }
+    }
+}
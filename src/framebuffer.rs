@@ -1,5 +1,8 @@
 // framebuffer.rs
 
+use image::{Rgb, RgbImage};
+use crate::error::GameError;
+
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
@@ -34,6 +37,25 @@ impl Framebuffer {
         }
     }
 
+    // Mezcla el color actual con el que ya está en (x, y); `alpha` 255 es opaco
+    pub fn blend_point(&mut self, x: usize, y: usize, alpha: u8) {
+        if alpha == 255 {
+            self.point(x, y);
+            return;
+        }
+        if alpha == 0 || x >= self.width || y >= self.height {
+            return;
+        }
+        let index = y * self.width + x;
+        let (src, dst, a) = (self.current_color, self.buffer[index], alpha as u32);
+        let mix = |shift: u32| {
+            let s = (src >> shift) & 0xFF;
+            let d = (dst >> shift) & 0xFF;
+            ((s * a + d * (255 - a) + 127) / 255) << shift
+        };
+        self.buffer[index] = mix(16) | mix(8) | mix(0);
+    }
+
     pub fn set_background_color(&mut self, color: u32) {
         self.background_color = color;
     }
@@ -47,6 +69,8 @@ impl Framebuffer {
         let mut cursor_x = x;
 
         for ch in text.chars() {
+            // La fuente solo tiene los 128 caracteres ASCII; los demás se dibujan como '?'
+            let ch = if ch.is_ascii() { ch } else { '?' };
             if let Some(index) = (ch as usize).checked_mul(8) {
                 for row in 0..8 {
                     let byte = font[index + row];
@@ -72,4 +96,15 @@ impl Framebuffer {
             }
         }
     }
-}
\ No newline at end of file
+
+    // Guarda el contenido del framebuffer como imagen PNG
+    pub fn save_png(&self, path: &str) -> Result<(), GameError> {
+        let image = RgbImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            let color = self.buffer[y as usize * self.width + x as usize];
+            Rgb([(color >> 16) as u8, (color >> 8) as u8, color as u8])
+        });
+        image
+            .save(path)
+            .map_err(|source| GameError::Image { path: path.to_string(), source })
+    }
+}
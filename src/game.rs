@@ -0,0 +1,130 @@
+// game.rs
+
+use minifb::Key;
+
+use crate::ai::update_enemy;
+use crate::combat::{apply_contact_damage, update_projectiles, update_weapon, Projectile};
+use crate::door::Doors;
+use crate::enemy::Enemy;
+use crate::input::InputSource;
+use crate::item::Keycard;
+use crate::lighting::Lighting;
+use crate::maze::Level;
+use crate::player::{check_win_condition, interact, pick_up_items, process_events, Player, BLOCK_SIZE};
+
+// Cómo va la partida después del último tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+    Playing,
+    Won,      // llegó a la salida con las tarjetas necesarias
+    TimeUp,   // se acabó el tiempo del nivel
+    Defeated, // los enemigos acabaron con el jugador
+}
+
+// Estado de una partida. No sabe nada de la ventana: avanza con `step` a partir de
+// cualquier `InputSource`, así que también sirve para pruebas y para incrustar el motor.
+pub struct Game {
+    pub level: Level,
+    pub player: Player,
+    pub enemies: Vec<Enemy>,
+    pub projectiles: Vec<Projectile>,
+    pub doors: Doors,
+    pub lighting: Lighting,
+    pub elapsed_time: f32,
+    required_items: Vec<Keycard>,
+    // Estado del tick anterior, para interpolar al dibujar
+    previous_player: Player,
+    previous_enemies: Vec<Enemy>,
+}
+
+impl Game {
+    pub fn new(level: Level) -> Self {
+        let block_size = BLOCK_SIZE as usize;
+        let maze_width = level.maze.iter().map(|row| row.len()).max().unwrap_or(0);
+        let doors = Doors::from_maze(&level.maze);
+        let lighting = Lighting::new(&level.lights, level.fog, maze_width, level.maze.len(), block_size);
+        let required_items = level.requirements.iter().copied().filter_map(Keycard::from_pickup).collect();
+        let player = Player::new(level.spawn_position(block_size), level.facing);
+        let enemies = level.spawn_enemies(block_size);
+
+        Game {
+            previous_player: player.clone(),
+            previous_enemies: enemies.clone(),
+            level,
+            player,
+            enemies,
+            projectiles: Vec::new(),
+            doors,
+            lighting,
+            elapsed_time: 0.0,
+            required_items,
+        }
+    }
+
+    // Avanza la simulación un tick de `dt` segundos
+    pub fn step(&mut self, input: &dyn InputSource, dt: f32) {
+        let block_size = BLOCK_SIZE as usize;
+        self.previous_player = self.player.clone();
+        self.previous_enemies = self.enemies.clone();
+
+        if input.is_key_pressed(Key::E) {
+            interact(&self.player, &self.level.maze, &mut self.doors, &self.enemies);
+        }
+        process_events(input, &mut self.player, &self.level, &self.doors, dt);
+        self.doors.update(dt);
+        pick_up_items(&mut self.player, &mut self.level.maze);
+        update_weapon(
+            &mut self.player,
+            input.is_key_down(Key::Space),
+            &mut self.enemies,
+            &self.level.maze,
+            &self.doors,
+            block_size,
+            dt,
+        );
+        for enemy in &mut self.enemies {
+            update_enemy(enemy, &self.player, &self.level, &self.doors, &mut self.projectiles, block_size, dt);
+        }
+        update_projectiles(
+            &mut self.projectiles,
+            &mut self.player,
+            &self.level.maze,
+            &self.level.theme,
+            &self.doors,
+            block_size,
+            dt,
+        );
+        apply_contact_damage(&self.enemies, &mut self.player, dt);
+        self.elapsed_time += dt;
+    }
+
+    pub fn time_left(&self) -> f32 {
+        (self.level.time_limit - self.elapsed_time).max(0.0)
+    }
+
+    // Ganar tiene prioridad si en el mismo tick también se acabó el tiempo
+    pub fn status(&self) -> GameStatus {
+        if check_win_condition(&self.player, &self.level.maze, &self.required_items) {
+            GameStatus::Won
+        } else if self.time_left() <= 0.0 {
+            GameStatus::TimeUp
+        } else if !self.player.is_alive() {
+            GameStatus::Defeated
+        } else {
+            GameStatus::Playing
+        }
+    }
+
+    // Jugador entre el tick anterior y el actual (`alpha` de 0 a 1)
+    pub fn view_player(&self, alpha: f32) -> Player {
+        self.player.interpolated(&self.previous_player, alpha)
+    }
+
+    pub fn view_enemies(&self, alpha: f32) -> Vec<Enemy> {
+        self.enemies
+            .iter()
+            .zip(&self.previous_enemies)
+            .map(|(enemy, previous)| enemy.interpolated(previous, alpha))
+            .collect()
+    }
+}
@@ -0,0 +1,57 @@
+// game_loop.rs
+
+use nalgebra_glm::Vec2;
+use std::f32::consts::PI;
+
+const MAX_FRAME_TIME: f32 = 0.25; // Evita la "espiral de la muerte" tras una pausa larga
+
+// Simulación a paso fijo: acumula el tiempo real de cada cuadro y lo
+// reparte en ticks de duración `dt`
+pub struct FixedTimestep {
+    pub dt: f32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(ticks_per_second: f32) -> Self {
+        FixedTimestep {
+            dt: 1.0 / ticks_per_second,
+            accumulator: 0.0,
+        }
+    }
+
+    // Agrega el tiempo real que tomó el último cuadro
+    pub fn accumulate(&mut self, frame_time: f32) {
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+    }
+
+    // Consume un tick si hay tiempo acumulado suficiente
+    pub fn step(&mut self) -> bool {
+        if self.accumulator >= self.dt {
+            self.accumulator -= self.dt;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Fracción (0.0 - 1.0) entre el tick anterior y el actual para interpolar al renderizar
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.dt
+    }
+}
+
+pub fn lerp_vec2(previous: Vec2, current: Vec2, alpha: f32) -> Vec2 {
+    previous + (current - previous) * alpha
+}
+
+// Interpola ángulos por el camino más corto, cruzando bien el paso de 2π a 0
+pub fn lerp_angle(previous: f32, current: f32, alpha: f32) -> f32 {
+    let mut delta = (current - previous) % (2.0 * PI);
+    if delta > PI {
+        delta -= 2.0 * PI;
+    } else if delta < -PI {
+        delta += 2.0 * PI;
+    }
+    previous + delta * alpha
+}
@@ -0,0 +1,48 @@
+// headless.rs
+
+use nalgebra_glm::Vec2;
+
+use crate::door::Doors;
+use crate::error::GameError;
+use crate::framebuffer::Framebuffer;
+use crate::lighting::Lighting;
+use crate::maze::validate::check;
+use crate::maze::Level;
+use crate::player::{Player, BLOCK_SIZE};
+use crate::render::{enemy_sprites, item_sprites, render3d, render_minimap, render_ui, GraphicsSettings};
+use crate::sprite::render_sprites;
+
+// Renderiza un cuadro del nivel sin ventana, con el jugador en `pos` mirando hacia `a`.
+// Los niveles que no se pueden jugar (por ejemplo, vacíos) se rechazan antes de dibujar.
+pub fn render_frame(level: &Level, pos: Vec2, a: f32, width: usize, height: usize) -> Result<Framebuffer, GameError> {
+    check(level)?;
+    let block_size = BLOCK_SIZE as usize;
+    let maze_width = level.maze.iter().map(|row| row.len()).max().unwrap_or(0);
+    let doors = Doors::from_maze(&level.maze);
+    let lighting = Lighting::new(&level.lights, level.fog, maze_width, level.maze.len(), block_size);
+    let player = Player::new(pos, a);
+
+    let mut framebuffer = Framebuffer::new(width, height);
+    let graphics = GraphicsSettings::default();
+    let z_buffer = render3d(&mut framebuffer, &player, level, &doors, &lighting, &graphics, 0.0);
+    let enemies = level.spawn_enemies(block_size);
+    let mut sprites = enemy_sprites(&enemies, &player);
+    sprites.extend(item_sprites(&level.maze));
+    render_sprites(&mut framebuffer, &player, sprites, &z_buffer, &lighting, &graphics);
+    render_ui(&mut framebuffer, &player);
+    render_minimap(&mut framebuffer, level, &player);
+
+    Ok(framebuffer)
+}
+
+// Igual que `render_frame`, guardando el resultado como PNG en `output_path`
+pub fn render_frame_to_png(
+    level: &Level,
+    pos: Vec2,
+    a: f32,
+    width: usize,
+    height: usize,
+    output_path: &str,
+) -> Result<(), GameError> {
+    render_frame(level, pos, a, width, height)?.save_png(output_path)
+}
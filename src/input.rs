@@ -0,0 +1,196 @@
+// input.rs
+
+use minifb::{Key, KeyRepeat, MouseMode, Window};
+
+// Fuente de entrada del juego: teclado y movimiento del mouse de un cuadro
+pub trait InputSource {
+    // Tecla mantenida presionada
+    fn is_key_down(&self, key: Key) -> bool;
+    // Tecla presionada en este cuadro (sin repetición)
+    fn is_key_pressed(&self, key: Key) -> bool;
+    // Movimiento del mouse (x, y) desde el cuadro anterior
+    fn mouse_delta(&self) -> (f32, f32);
+}
+
+// Entrada leída de una ventana de minifb; `poll` toma una foto por cuadro
+pub struct MinifbInput {
+    keys_down: Vec<Key>,
+    keys_pressed: Vec<Key>,
+    last_mouse: Option<(f32, f32)>,
+    mouse_delta: (f32, f32),
+}
+
+impl MinifbInput {
+    pub fn new() -> Self {
+        MinifbInput {
+            keys_down: Vec::new(),
+            keys_pressed: Vec::new(),
+            last_mouse: None,
+            mouse_delta: (0.0, 0.0),
+        }
+    }
+
+    pub fn poll(&mut self, window: &Window) {
+        self.keys_down = window.get_keys();
+        self.keys_pressed = window.get_keys_pressed(KeyRepeat::No);
+
+        let mouse = window.get_mouse_pos(MouseMode::Discard);
+        self.mouse_delta = match (mouse, self.last_mouse) {
+            (Some((x, y)), Some((last_x, last_y))) => (x - last_x, y - last_y),
+            _ => (0.0, 0.0),
+        };
+        self.last_mouse = mouse;
+    }
+}
+
+impl Default for MinifbInput {
+    fn default() -> Self {
+        MinifbInput::new()
+    }
+}
+
+impl InputSource for MinifbInput {
+    fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    fn mouse_delta(&self) -> (f32, f32) {
+        self.mouse_delta
+    }
+}
+
+// Entrada que ven los ticks de la simulación. Las teclas recién presionadas y el movimiento
+// del mouse se juntan cuadro a cuadro hasta que un tick los consume: en un cuadro con
+// varios ticks solo el primero los ve, y en uno sin ticks pasan al cuadro siguiente.
+#[derive(Default)]
+pub struct TickInput {
+    keys_down: Vec<Key>,
+    keys_pressed: Vec<Key>,
+    mouse_delta: (f32, f32),
+}
+
+impl TickInput {
+    pub fn new() -> Self {
+        TickInput::default()
+    }
+
+    // Agrega la entrada de un cuadro de la ventana
+    pub fn gather(&mut self, frame: &MinifbInput) {
+        self.keys_down.clone_from(&frame.keys_down);
+        for &key in &frame.keys_pressed {
+            if !self.keys_pressed.contains(&key) {
+                self.keys_pressed.push(key);
+            }
+        }
+        self.mouse_delta.0 += frame.mouse_delta.0;
+        self.mouse_delta.1 += frame.mouse_delta.1;
+    }
+
+    // Descarta los eventos que ya vio un tick; las teclas mantenidas siguen igual
+    pub fn consume_events(&mut self) {
+        self.keys_pressed.clear();
+        self.mouse_delta = (0.0, 0.0);
+    }
+}
+
+impl InputSource for TickInput {
+    fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    fn mouse_delta(&self) -> (f32, f32) {
+        self.mouse_delta
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputEvent {
+    KeyDown(Key),
+    KeyUp(Key),
+    MouseMove(f32, f32),
+}
+
+// Evento que ocurre `time` segundos después de iniciar el guion
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedInput {
+    pub time: f32,
+    pub event: InputEvent,
+}
+
+// Reproduce una lista de entradas con tiempo, para pruebas y demos
+pub struct ScriptedInput {
+    script: Vec<TimedInput>,
+    next: usize,
+    clock: f32,
+    keys_down: Vec<Key>,
+    keys_pressed: Vec<Key>,
+    mouse_delta: (f32, f32),
+}
+
+impl ScriptedInput {
+    pub fn new(mut script: Vec<TimedInput>) -> Self {
+        script.sort_by(|a, b| a.time.total_cmp(&b.time));
+        ScriptedInput {
+            script,
+            next: 0,
+            clock: 0.0,
+            keys_down: Vec::new(),
+            keys_pressed: Vec::new(),
+            mouse_delta: (0.0, 0.0),
+        }
+    }
+
+    // Avanza el reloj `dt` segundos y aplica los eventos que ya ocurrieron
+    pub fn advance(&mut self, dt: f32) {
+        self.clock += dt;
+        self.keys_pressed.clear();
+        self.mouse_delta = (0.0, 0.0);
+
+        while let Some(input) = self.script.get(self.next) {
+            if input.time > self.clock {
+                break;
+            }
+            match input.event {
+                InputEvent::KeyDown(key) => {
+                    if !self.keys_down.contains(&key) {
+                        self.keys_down.push(key);
+                        self.keys_pressed.push(key);
+                    }
+                }
+                InputEvent::KeyUp(key) => self.keys_down.retain(|&k| k != key),
+                InputEvent::MouseMove(dx, dy) => {
+                    self.mouse_delta.0 += dx;
+                    self.mouse_delta.1 += dy;
+                }
+            }
+            self.next += 1;
+        }
+    }
+
+    // Verdadero cuando ya se aplicaron todos los eventos del guion
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.script.len()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    fn mouse_delta(&self) -> (f32, f32) {
+        self.mouse_delta
+    }
+}
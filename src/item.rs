@@ -0,0 +1,38 @@
+// item.rs
+
+// Tarjetas de acceso: 'r', 'b', 'y' se recogen en el laberinto y
+// abren las barreras 'R', 'B', 'Y' del mismo color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Keycard {
+    Red,
+    Blue,
+    Yellow,
+}
+
+impl Keycard {
+    pub fn from_pickup(cell: char) -> Option<Self> {
+        match cell {
+            'r' => Some(Keycard::Red),
+            'b' => Some(Keycard::Blue),
+            'y' => Some(Keycard::Yellow),
+            _ => None,
+        }
+    }
+
+    pub fn from_barrier(cell: char) -> Option<Self> {
+        Keycard::from_pickup(cell.to_ascii_lowercase()).filter(|_| cell.is_ascii_uppercase())
+    }
+
+    pub fn color(self) -> u32 {
+        match self {
+            Keycard::Red => 0xDD2222,
+            Keycard::Blue => 0x2255DD,
+            Keycard::Yellow => 0xDDCC22,
+        }
+    }
+}
+
+// Una barrera desaparece cuando el jugador tiene la tarjeta de su color
+pub fn is_open_barrier(cell: char, inventory: &[Keycard]) -> bool {
+    Keycard::from_barrier(cell).is_some_and(|key| inventory.contains(&key))
+}
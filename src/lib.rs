@@ -0,0 +1,24 @@
+// lib.rs
+
+pub mod framebuffer;
+pub mod maze;
+pub mod player;
+pub mod raycast;
+pub mod texture;
+pub mod enemy;
+pub mod audio;
+pub mod display_stats;
+pub mod screen;
+pub mod lighting;
+pub mod door;
+pub mod item;
+pub mod error;
+pub mod render;
+pub mod headless;
+pub mod input;
+pub mod game_loop;
+pub mod theme;
+pub mod ai;
+pub mod combat;
+pub mod sprite;
+pub mod game;
@@ -0,0 +1,114 @@
+// lighting.rs
+
+use nalgebra_glm::Vec2;
+use crate::raycast::Face;
+
+const AMBIENT_LIGHT: f32 = 0.35; // Luz mínima en niveles con fuentes de luz
+const LIGHT_RADIUS: f32 = 4.0; // Alcance de cada fuente de luz, en celdas
+const SIDE_SHADE: f32 = 0.75; // Oscurecimiento de las caras este/oeste
+
+// Niebla que tapa lo lejano: a `distance` unidades queda `1 - e^(-density * distance)` del color
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fog {
+    pub color: u32,
+    pub density: f32,
+}
+
+impl Default for Fog {
+    fn default() -> Self {
+        Fog { color: 0x2B2E3D, density: 0.002 }
+    }
+}
+
+pub struct Lighting {
+    pub fog: Fog,
+    light_map: Vec<Vec<f32>>,
+    block_size: f32,
+}
+
+impl Lighting {
+    // `lights` marca con '*' las celdas que son fuentes de luz
+    pub fn new(lights: &[Vec<char>], fog: Fog, width: usize, height: usize, block_size: usize) -> Self {
+        let sources: Vec<(f32, f32)> = lights
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.iter()
+                    .enumerate()
+                    .filter(|(_, &cell)| cell == '*')
+                    .map(move |(col, _)| (col as f32, row as f32))
+            })
+            .collect();
+
+        // Sin fuentes de luz el nivel queda completamente iluminado
+        let light_map = (0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|col| {
+                        if sources.is_empty() {
+                            return 1.0;
+                        }
+                        let light = sources.iter().fold(AMBIENT_LIGHT, |acc, &(sx, sy)| {
+                            let d = ((col as f32 - sx).powi(2) + (row as f32 - sy).powi(2)).sqrt();
+                            acc + (1.0 - d / LIGHT_RADIUS).max(0.0)
+                        });
+                        light.min(1.0)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Lighting {
+            fog,
+            light_map,
+            block_size: block_size as f32,
+        }
+    }
+
+    // Nivel de luz (0.0 - 1.0) en una posición del mundo
+    pub fn light_at(&self, pos: Vec2) -> f32 {
+        if pos.x < 0.0 || pos.y < 0.0 {
+            return AMBIENT_LIGHT;
+        }
+        let col = (pos.x / self.block_size) as usize;
+        let row = (pos.y / self.block_size) as usize;
+        self.light_map
+            .get(row)
+            .and_then(|line| line.get(col))
+            .copied()
+            .unwrap_or(AMBIENT_LIGHT)
+    }
+
+    // Aplica la luz de `pos` y la niebla según la distancia
+    pub fn shade(&self, color: u32, distance: f32, pos: Vec2) -> u32 {
+        let lit = scale_color(color, self.light_at(pos));
+        let fog = 1.0 - (-self.fog.density * distance).exp();
+        mix_color(lit, self.fog.color, fog)
+    }
+
+    // Igual que `shade`, oscureciendo además las caras este/oeste de las paredes
+    pub fn shade_wall(&self, color: u32, distance: f32, pos: Vec2, face: Face) -> u32 {
+        let color = match face {
+            Face::East | Face::West => scale_color(color, SIDE_SHADE),
+            Face::North | Face::South => color,
+        };
+        self.shade(color, distance, pos)
+    }
+}
+
+fn scale_color(color: u32, factor: f32) -> u32 {
+    let r = (((color >> 16) & 0xFF) as f32 * factor) as u32;
+    let g = (((color >> 8) & 0xFF) as f32 * factor) as u32;
+    let b = ((color & 0xFF) as f32 * factor) as u32;
+    (r.min(255) << 16) | (g.min(255) << 8) | b.min(255)
+}
+
+pub fn mix_color(a: u32, b: u32, t: f32) -> u32 {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |shift: u32| {
+        let ca = ((a >> shift) & 0xFF) as f32;
+        let cb = ((b >> shift) & 0xFF) as f32;
+        ((ca + (cb - ca) * t) as u32).min(255) << shift
+    };
+    channel(16) | channel(8) | channel(0)
+}
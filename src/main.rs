@@ -1,221 +1,58 @@
 // main.rs
 
 use minifb::{Key, Window, WindowOptions};
-use std::time::{Instant, Duration};
+use std::time::Duration;
 //use std::f32::consts::PI;
 use nalgebra_glm::Vec2;
 //use std::process;
-use once_cell::sync::Lazy;
-use std::sync::Arc;
-
-mod framebuffer;
-mod maze;
-mod player;
-mod raycast;
-mod texture;
-mod enemy;
-mod audio;
-mod display_stats;
-mod screen;
-
-use framebuffer::Framebuffer;
-use maze::load_maze;
-use player::{Player, process_events, check_win_condition};
-use raycast::cast_ray;
-use texture::Texture;
-use enemy::{Enemy, ENEMY_TEXTURE};
-use audio::AudioPlayer;
-use display_stats::Timer;
-use screen::Screen;
-
-static WALL1: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/wall1.jpg")));
-static WALL2: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/wall2.jpg")));
-static WALL3: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/wall3.jpg")));
-static WALL4: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/wall4.jpg")));
-static WALL5: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/wall5.jpg")));
-static UI_SPRITE: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/player2.png")));
-const TRANSPARENT_COLOR: u32 = 0xED1C24;
-
-
-fn cell_to_color(cell: char) -> u32 {
-    let default_color = 0x000000;
-    match cell {
-        '+' => 0xFF00FF,
-        '-'  => 0xDD11DD,
-        'g' => 0xFF00,
-        '|' => 0xCC11CC,
-        _ => default_color,
-    }
-}
-
-fn cell_to_texture_color(cell: char, tx: u32, ty: u32) -> u32 {
-    match cell {
-        '+' => WALL4.get_pixel_color(tx, ty),
-        '-' => WALL2.get_pixel_color(tx, ty),
-        '|' => WALL1.get_pixel_color(tx, ty),
-        'g' => WALL5.get_pixel_color(tx, ty),
-        _ => WALL3.get_pixel_color(tx, ty),
-    }
-}
 
-fn draw_cell(framebuffer: &mut Framebuffer, xo: usize, yo: usize, block_size: usize, cell: char) {
-    for x in xo..xo + block_size {
-        for y in yo..yo + block_size {
-            if cell != ' ' {
-                let color = cell_to_color(cell);
-                framebuffer.set_current_color(color);
-                framebuffer.point(x, y);               
-            }
-        }
-    }   
+use graficas_proy1::framebuffer::Framebuffer;
+use graficas_proy1::maze::{try_load_level, Level};
+use graficas_proy1::maze::validate::check;
+use graficas_proy1::maze::generate::{generate, time_limit, Algorithm, SPAWN_BLOCK};
+use graficas_proy1::audio::{AudioPlayer, play_sound};
+use graficas_proy1::display_stats::Timer;
+use graficas_proy1::screen::Screen;
+use graficas_proy1::game::{Game, GameStatus};
+use graficas_proy1::theme::{Theme, DEFAULT_THEME};
+use graficas_proy1::error::GameError;
+use graficas_proy1::headless::render_frame_to_png;
+use graficas_proy1::input::{InputSource, MinifbInput, TickInput};
+use graficas_proy1::game_loop::FixedTimestep;
+use graficas_proy1::render::{enemy_sprites, item_sprites, projectile_sprites, render2d, render3d, render_ui, render_minimap, GraphicsSettings};
+use graficas_proy1::texture::TextureFilter;
+use graficas_proy1::sprite::render_sprites;
+
+// Nivel elegido en la pantalla de bienvenida
+enum LevelChoice {
+    File(String),
+    Random { size: usize, algorithm: Algorithm, seed: u64 },
 }
 
-fn draw_sprite(framebuffer: &mut Framebuffer, player: &Player, enemy: &Enemy, z_buffer: &mut [f32]) {
+// Las semillas tienen a lo más 6 cifras para que se puedan anotar y volver a escribir
+const MAX_SEED: u64 = 999_999;
 
-    let sprite_a = (enemy.pos.y - player.pos.y).atan2(enemy.pos.x - player.pos.x);
-  
-    if sprite_a < -1.0 {  // sprite is behind
-        return;
-    }
-  
-    let sprite_d = ((player.pos.x - enemy.pos.x).powi(2) + (player.pos.y - enemy.pos.y).powi(2)).sqrt();
-    
-    if sprite_d < 50.0 {
-        return;
-    }
-  
-    let screen_height = framebuffer.height as f32;
-    let screen_width = framebuffer.width as f32;
-    let sprite_size = (screen_height / sprite_d) * 70.0;
-    let start_x = ((sprite_a - player.a) * (screen_height / player.fov) + screen_width / 2.0 - sprite_size / 2.0) as f32;
-    let start_y = ((screen_height / 2.0) - (sprite_size / 2.0)) as f32;
-    let sprite_size = sprite_size as usize;
-    // println!("sprite_a: {:#?} sprite_d: {:#?} sprite_size: {:#?}", sprite_a, sprite_d, sprite_size);
-  
-    let start_x = start_x.max(0.0) as usize;
-    let start_y = start_y.max(0.0) as usize;
-    let end_x = (start_x + sprite_size).min(framebuffer.width);
-    let end_y = (start_y + sprite_size).min(framebuffer.height);
-  
-    for x in start_x..end_x {
-      // Check if this column of the sprite is in front of what's in the z-buffer
-        if sprite_d < z_buffer[x] {
-            for y in start_y..end_y {
-                let tx = ((x - start_x) * 128 / sprite_size as usize) as u32;
-                let ty = ((y - start_y) * 128 / sprite_size as usize) as u32;
-                let color = ENEMY_TEXTURE.get_pixel_color(tx, ty);
-                if color != TRANSPARENT_COLOR {
-                framebuffer.set_current_color(color);
-                framebuffer.point(x, y);
-                }
-            }
-            // Update the z-buffer for this column 
-            z_buffer[x] = sprite_d;
-        }
-    }
+// Semilla inicial para los niveles aleatorios
+fn time_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|time| time.as_nanos() as u64 % (MAX_SEED + 1))
+        .unwrap_or(0)
 }
 
-fn render_ui(framebuffer: &mut Framebuffer) {
-    let ui_width = 512 as u32; // Adjust this to match your UI sprite width
-    let ui_height = 512 as u32; // Adjust this to match your UI sprite height
-    let ui_x = ((framebuffer.width as f32 / 2.0) - (ui_width as f32 / 2.0)) as u32; // X position of the UI sprite
-    let ui_y = (framebuffer.height - ui_height as usize) as u32; // Y position of the UI sprite
-  
-    for y in 0..ui_height {
-        for x in 0..ui_width {
-            let color = UI_SPRITE.get_pixel_color(x as u32, y as u32);
-            if color != TRANSPARENT_COLOR {
-                framebuffer.set_current_color(color);
-                framebuffer.point((ui_x + x) as usize, (ui_y + y) as usize);
-            }
-        }
-    }
-}
+// Títulos de la pantalla de error
+const LEVEL_ERROR: &str = "No se pudo cargar el nivel";
+const SCREEN_ERROR: &str = "No se pudo cargar una pantalla";
 
-fn render2d(framebuffer: &mut Framebuffer, player: &Player, maze: &Vec<Vec<char>>) {
-    
-    let block_size = 70; //100
-  
-    // draw the minimap
-    for row in 0..maze.len() {
-        for col in 0..maze[row].len() {
-            draw_cell(framebuffer, col * block_size, row * block_size, block_size, maze[row][col]);
-        }
-    }
-    // draw the player
-    framebuffer.set_current_color(0xFFDDDD);
-    framebuffer.point(player.pos.x as usize, player.pos.y as usize);
-  
-    // draw what the player sees
-    let num_rays = 50;
-    for i in 0..num_rays {
-        let current_ray = i as f32 / num_rays as f32; // current ray divided by total rays
-        let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
-        cast_ray(framebuffer, &maze, &player, a, block_size, true);
-    }
-}
-  
-fn render3d(framebuffer: &mut Framebuffer, player: &Player, z_buffer: &mut [f32], maze: &Vec<Vec<char>>) {
-    
-    let block_size = 70; 
-    let num_rays = framebuffer.width;
-  
-    // Precalculate half height of the framebuffer
-    let hh = framebuffer.height as f32 / 2.0;  
-  
-    // draw the sky and the floor
-    for i in 0..framebuffer.width {
-        framebuffer.set_current_color(0x2B2E3D);
-        for j in 0..(framebuffer.height / 2) {
-            framebuffer.point(i, j);
-        }
-        framebuffer.set_current_color(0x222530);
-        for j in (framebuffer.height / 2)..framebuffer.height {
-            framebuffer.point(i, j);
-        }
-    }
-  
-    framebuffer.set_current_color(0x222530);
-  
-    for i in 0..num_rays {
-        let current_ray = i as f32 / num_rays as f32;
-        let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
-        let intersect = cast_ray(framebuffer, &maze, &player, a, block_size, false);
-
-        let distance_to_wall = intersect.distance;
-        let distance_to_projection_plane = 70.0;
-        let stake_height = (hh / distance_to_wall) * distance_to_projection_plane;
-
-        let stake_top = (hh - (stake_height / 2.0)) as usize;
-        let stake_bottom = (hh + (stake_height / 2.0)) as usize;
-
-        z_buffer[i] = distance_to_wall;
-  
-        for y in stake_top..stake_bottom {
-            let ty = (y as f32 - stake_top as f32) / (stake_bottom as f32 - stake_top as f32) * 128.0; // texture
-            let color = cell_to_texture_color(intersect.impact, intersect.tx as u32, ty as u32);
-            framebuffer.set_current_color(color);
-            framebuffer.point(i, y);
-        }
-    }
-}
-  
-fn render_enemies(framebuffer: &mut Framebuffer, player: &Player, z_buffer: &mut [f32]) {
-    let enemies = vec![
-        Enemy::new(250.0, 250.0),
-        Enemy::new(450.0, 450.0),
-        Enemy::new(650.0, 650.0),
-    ];
-  
-    for enemy in &enemies {
-        draw_sprite(framebuffer, &player, enemy, z_buffer);
-    }
-}
+const DIGIT_KEYS: [Key; 10] = [
+    Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4,
+    Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+];
 
-fn show_welcome_screen(window: &mut Window, framebuffer: &mut Framebuffer) -> String {
+fn show_welcome_screen(window: &mut Window, input: &mut MinifbInput, framebuffer: &mut Framebuffer) -> Result<LevelChoice, GameError> {
     // Crear la pantalla de bienvenida
     let mut welcome_screen = Screen::new();
-    welcome_screen.set_background("assets/main_screen.jpg"); // Imagen de fondo
+    welcome_screen.set_background("assets/main_screen.jpg")?; // Imagen de fondo
 
     // Título e instrucciones
     welcome_screen.add_text(
@@ -233,9 +70,15 @@ fn show_welcome_screen(window: &mut Window, framebuffer: &mut Framebuffer) -> St
         0xFFFFFF, // Blanco
     );
 
-    let levels = vec!["Nivel 1", "Nivel 2", "Nivel 3"];
+    let levels = vec!["Nivel 1", "Nivel 2", "Nivel 3", "Nivel aleatorio"];
+    let random_level = levels.len() - 1;
     let mut selected_level = 0; // Índice del nivel seleccionado
 
+    // Opciones del nivel aleatorio
+    let mut random_size: usize = 6;
+    let mut algorithm_index = 0;
+    let mut seed = time_seed();
+
     loop {
         // Actualizar los textos dinámicos (niveles)
         welcome_screen.text.truncate(2); // Mantener solo título e instrucciones
@@ -249,6 +92,21 @@ fn show_welcome_screen(window: &mut Window, framebuffer: &mut Framebuffer) -> St
                 color,
             );
         }
+        if selected_level == random_level {
+            let algorithm = Algorithm::ALL[algorithm_index];
+            welcome_screen.add_text(
+                &format!(
+                    "Tamano {0}x{0} (<- ->)  Algoritmo {1} (A)  Semilla {2} (S, 0-9)",
+                    random_size,
+                    algorithm.name(),
+                    seed
+                ),
+                framebuffer.width / 2 - 320,
+                framebuffer.height / 2 + levels.len() * 30 + 10,
+                1,
+                0xFFFFFF,
+            );
+        }
 
         // Renderizar la pantalla
         welcome_screen.render(framebuffer);
@@ -257,29 +115,59 @@ fn show_welcome_screen(window: &mut Window, framebuffer: &mut Framebuffer) -> St
             .unwrap();
 
         // Procesar entrada del usuario
-        if window.is_key_down(Key::Up) && selected_level > 0 {
+        input.poll(window);
+        if input.is_key_pressed(Key::Up) && selected_level > 0 {
             selected_level -= 1; // Mover hacia arriba en la lista
         }
-        if window.is_key_down(Key::Down) && selected_level < levels.len() - 1 {
+        if input.is_key_pressed(Key::Down) && selected_level < levels.len() - 1 {
             selected_level += 1; // Mover hacia abajo en la lista
         }
-        if window.is_key_down(Key::Enter) {
+        if selected_level == random_level {
+            if input.is_key_pressed(Key::Left) && random_size > 2 {
+                random_size -= 1;
+            }
+            if input.is_key_pressed(Key::Right) && random_size < 30 {
+                random_size += 1;
+            }
+            if input.is_key_pressed(Key::A) {
+                algorithm_index = (algorithm_index + 1) % Algorithm::ALL.len();
+            }
+            if input.is_key_pressed(Key::S) {
+                seed = time_seed();
+            }
+            // Escribir una semilla: las cifras se agregan a la derecha y Backspace borra la última
+            for (digit, &key) in DIGIT_KEYS.iter().enumerate() {
+                if input.is_key_pressed(key) && seed * 10 + digit as u64 <= MAX_SEED {
+                    seed = seed * 10 + digit as u64;
+                }
+            }
+            if input.is_key_pressed(Key::Backspace) {
+                seed /= 10;
+            }
+        }
+        if input.is_key_pressed(Key::Enter) {
+            if selected_level == random_level {
+                return Ok(LevelChoice::Random {
+                    size: random_size,
+                    algorithm: Algorithm::ALL[algorithm_index],
+                    seed,
+                });
+            }
             // Devuelve el archivo de laberinto correspondiente al nivel seleccionado
-            return format!("maze{}.txt", selected_level + 1);
+            return Ok(LevelChoice::File(format!("maze{}.txt", selected_level + 1)));
         }
 
-        // Evitar detección repetida de teclas
-        std::thread::sleep(Duration::from_millis(100));
+        std::thread::sleep(Duration::from_millis(16));
     }
 }
 
 
-fn show_win_screen(window: &mut Window, framebuffer: &mut Framebuffer) {
+fn show_win_screen(window: &mut Window, input: &mut MinifbInput, framebuffer: &mut Framebuffer) -> Result<(), GameError> {
 
 	// Crear la pantalla de victoria
 	let mut win_screen = Screen::new();
 	let color = 0xFFFFFF; // Color blanco
-	win_screen.set_background("assets/win_screen.jpg"); // Establece una imagen de fondo
+	win_screen.set_background("assets/win_screen.jpg")?; // Establece una imagen de fondo
 	win_screen.add_text(
 		"Has escapado de la Death Star",
 		framebuffer.width / 2 - 320,
@@ -304,19 +192,21 @@ fn show_win_screen(window: &mut Window, framebuffer: &mut Framebuffer) {
 			.unwrap();
 
 		// Salir del bucle al presionar Esc
-		if window.is_key_down(Key::Escape) {
+		input.poll(window);
+		if input.is_key_down(Key::Escape) {
 			break;
 		}
 
 		std::thread::sleep(Duration::from_millis(16));
 	}
+	Ok(())
 }
 
-fn show_defeat_screen(window: &mut Window, framebuffer: &mut Framebuffer) {
+fn show_defeat_screen(window: &mut Window, input: &mut MinifbInput, framebuffer: &mut Framebuffer) -> Result<(), GameError> {
     // Crear la pantalla de derrota
     let mut defeat_screen = Screen::new();
 	let color = 0xFFFFFF; // Color blanco
-	defeat_screen.set_background("assets/lose_screen.jpg"); // Establece una imagen de fondo
+	defeat_screen.set_background("assets/lose_screen.jpg")?; // Establece una imagen de fondo
 	defeat_screen.add_text(
 		"No has logrado escapar",
 		framebuffer.width / 2 - 245,
@@ -340,68 +230,128 @@ fn show_defeat_screen(window: &mut Window, framebuffer: &mut Framebuffer) {
 			.unwrap();
 
 		// Salir del bucle al presionar Esc
-		if window.is_key_down(Key::Escape) {
+		input.poll(window);
+		if input.is_key_down(Key::Escape) {
 			break;
 		}
 
 		std::thread::sleep(Duration::from_millis(16));
 	}
+	Ok(())
 }
 
-fn render_minimap(framebuffer: &mut Framebuffer, maze: &Vec<Vec<char>>, player: &Player) {
-    // Configuración del minimapa
-    let minimap_size = 100; // Tamaño total del minimapa
-    let cell_size = minimap_size / maze.len(); // Tamaño de cada celda del minimapa
-    let minimap_x = framebuffer.width - minimap_size - 200; // Margen derecho
-    let minimap_y = 5; // Margen superior
-
-    // Dibujar el minimapa
-    for (row, line) in maze.iter().enumerate() {
-		for (col, &cell) in line.iter().enumerate() {
-			let x = minimap_x + col * cell_size;
-			let y = minimap_y + row * cell_size;
-	
-			// Dibujar la celda solo si está dentro de los límites del framebuffer
-			if x + cell_size < framebuffer.width && y + cell_size < framebuffer.height {
-				let color = match cell {
-					'+' | '-' | '|' => 0x888888, // Pared (gris)
-					'g' => 0x00FF00,            // Meta (verde)
-					_ => 0x000000,              // Espacio vacío (negro)
-				};
-	
-				for i in 0..cell_size {
-					for j in 0..cell_size {
-						framebuffer.set_current_color(color);
-						framebuffer.point(x + i, y + j);
-					}
-				}
-			}
-		}
+fn show_error_screen(window: &mut Window, input: &mut MinifbInput, framebuffer: &mut Framebuffer, title: &str, error: &GameError) {
+	// Crear la pantalla de error (sin fondo, por si el error es de una imagen)
+	let mut error_screen = Screen::new();
+	error_screen.add_text(title, 40, framebuffer.height / 3, 3, 0xFF4444);
+
+	// Partir el mensaje en líneas que quepan en la pantalla
+	let message: Vec<char> = error.to_string().chars().collect();
+	let chars_per_line = (framebuffer.width - 80) / 9;
+	for (i, line) in message.chunks(chars_per_line).enumerate() {
+		let line: String = line.iter().collect();
+		error_screen.add_text(&line, 40, framebuffer.height / 2 + i * 15, 1, 0xFFFFFF);
 	}
+	error_screen.add_text(
+		"Presiona Esc para salir",
+		40,
+		framebuffer.height - 60,
+		2,
+		0xFFFFFF,
+	);
 
-    // Dibujar al jugador en el minimapa
-    let player_col = (player.pos.x / 70.0) as usize; // Escalado del jugador según el bloque
-    let player_row = (player.pos.y / 70.0) as usize;
-    let player_x = minimap_x + player_col * cell_size + cell_size / 2;
-    let player_y = minimap_y + player_row * cell_size + cell_size / 2;
-
-    let player_color = 0xFF0000; // Color rojo para el jugador
-    let player_radius = (cell_size / 4) as isize;
-
-    for i in -player_radius..=player_radius {
-		for j in -player_radius..=player_radius {
-			let px = player_x as isize + i;
-			let py = player_y as isize + j;
-	
-			// Asegurarnos de que px y py están dentro de los límites
-			if px >= 0 && py >= 0 && px < framebuffer.width as isize && py < framebuffer.height as isize {
-				framebuffer.set_current_color(player_color);
-				framebuffer.point(px as usize, py as usize); // Convertir de nuevo a usize para dibujar
-			}
+	// Mostrar la pantalla de error
+	while window.is_open() {
+		error_screen.render(framebuffer);
+		window
+			.update_with_buffer(&framebuffer.buffer, framebuffer.width, framebuffer.height)
+			.unwrap();
+
+		// Salir del bucle al presionar Esc
+		input.poll(window);
+		if input.is_key_down(Key::Escape) {
+			break;
 		}
+
+		std::thread::sleep(Duration::from_millis(16));
 	}
 }
 
+// Valida el laberinto: imprime las advertencias y devuelve un error si no se puede jugar
+fn check_level(path: &str, level: &Level) -> Result<(), GameError> {
+    // `check` nombra el nivel por su nombre; aquí se muestra el archivo
+    let warnings = check(level).map_err(|err| match err {
+        GameError::InvalidLevel { message, .. } => GameError::InvalidLevel { path: path.to_string(), message },
+        err => err,
+    })?;
+    for warning in warnings {
+        eprintln!("{}: advertencia: {}", path, warning);
+    }
+    Ok(())
+}
+
+// --check-level <nivel>
+fn run_check_level(args: &[String]) {
+    let [path] = args else {
+        eprintln!("Uso: --check-level <nivel>");
+        std::process::exit(2);
+    };
+
+    let result = try_load_level(path).and_then(|level| check_level(path, &level));
+    match result {
+        Ok(()) => println!("{}: el nivel es valido", path),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+// --render-frame <nivel> <x> <y> <ángulo> <salida.png> [ancho alto]
+fn run_render_frame(args: &[String], width: usize, height: usize) {
+    let usage = "Uso: --render-frame <nivel> <x> <y> <ángulo en radianes> <salida.png> [ancho alto]";
+    let (level, x, y, a, output, size) = match args {
+        [level, x, y, a, output] => (level, x, y, a, output, Ok((width, height))),
+        [level, x, y, a, output, w, h] => (level, x, y, a, output, w.parse().and_then(|w| Ok((w, h.parse()?)))),
+        _ => {
+            eprintln!("{}", usage);
+            std::process::exit(2);
+        }
+    };
+    let (Ok(x), Ok(y), Ok(a), Ok((width, height))) = (x.parse::<f32>(), y.parse::<f32>(), a.parse::<f32>(), size) else {
+        eprintln!("{}", usage);
+        std::process::exit(2);
+    };
+    if width == 0 || height == 0 {
+        eprintln!("{}", usage);
+        std::process::exit(2);
+    }
+
+    let result = try_load_level(level).and_then(|level| render_frame_to_png(&level, Vec2::new(x, y), a, width, height, output));
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+    println!("Cuadro guardado en {}", output);
+}
+
+// --random <tamaño> <algoritmo> <semilla>: juega directamente un nivel aleatorio
+fn parse_random_args(args: &[String]) -> LevelChoice {
+    let usage = "Uso: --random <tamano> <backtracker|prim|kruskal|wilson> <semilla>";
+    let [size, algorithm, seed] = args else {
+        eprintln!("{}", usage);
+        std::process::exit(2);
+    };
+    match (size.parse::<usize>(), Algorithm::from_name(algorithm), seed.parse::<u64>()) {
+        (Ok(size), Some(algorithm), Ok(seed)) if (2..=30).contains(&size) => {
+            LevelChoice::Random { size, algorithm, seed }
+        }
+        _ => {
+            eprintln!("{}", usage);
+            std::process::exit(2);
+        }
+    }
+}
 
 fn main() {
     let window_width = 900; //1300
@@ -410,6 +360,18 @@ fn main() {
     let framebuffer_width = 900; //1300
     let framebuffer_height = 635; //900
 
+    // Modos sin ventana: renderizar un cuadro a PNG o revisar un nivel, y terminar
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--render-frame") {
+        run_render_frame(&args[2..], framebuffer_width, framebuffer_height);
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("--check-level") {
+        run_check_level(&args[2..]);
+        return;
+    }
+    let random_choice = (args.get(1).map(String::as_str) == Some("--random")).then(|| parse_random_args(&args[2..]));
+
     let frame_delay = Duration::from_millis(0);
 
     let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
@@ -428,97 +390,169 @@ fn main() {
         WindowOptions::default(),
     ).unwrap();
 
-	// Mostrar la pantalla de bienvenida y obtener el nivel seleccionado
-	let selected_level = show_welcome_screen(&mut window, &mut framebuffer);
+	let mut input = MinifbInput::new();
+
+	// Mostrar la pantalla de bienvenida y obtener el nivel seleccionado (salvo con --random)
+	let selected_level = match random_choice {
+		Some(choice) => choice,
+		None => match show_welcome_screen(&mut window, &mut input, &mut framebuffer) {
+			Ok(choice) => choice,
+			Err(err) => {
+				eprintln!("{}", err);
+				show_error_screen(&mut window, &mut input, &mut framebuffer, SCREEN_ERROR, &err);
+				return;
+			}
+		},
+	};
 
 	// Cargar el laberinto correspondiente al nivel seleccionado
-	let maze = load_maze(&selected_level);
-
-    // Inicializa al jugador
-    let mut player = Player {
-        pos: Vec2::new(150.0, 150.0),
-        a: std::f32::consts::PI / 3.0,
-        fov: std::f32::consts::PI / 3.0,
-        last_mouse_x: None,
-    };
+	let level = match &selected_level {
+		LevelChoice::File(path) => try_load_level(path),
+		LevelChoice::Random { size, algorithm, seed } => {
+			// En los niveles aleatorios el jugador aparece en la primera celda
+			Theme::load(DEFAULT_THEME).map(|theme| {
+				let mut level = Level::new(&format!("Aleatorio {}", seed), generate(*size, *size, *algorithm, *seed));
+				level.theme = theme;
+				level.spawn = SPAWN_BLOCK;
+				level.time_limit = time_limit(&level.maze, SPAWN_BLOCK);
+				level
+			})
+		}
+	};
+	let level = match level {
+		Ok(level) => level,
+		Err(err) => {
+			eprintln!("{}", err);
+			show_error_screen(&mut window, &mut input, &mut framebuffer, LEVEL_ERROR, &err);
+			return;
+		}
+	};
+
+	// Revisar que el nivel se pueda jugar antes de empezar
+	if let LevelChoice::File(path) = &selected_level {
+		if let Err(err) = check_level(path, &level) {
+			eprintln!("{}", err);
+			show_error_screen(&mut window, &mut input, &mut framebuffer, LEVEL_ERROR, &err);
+			return;
+		}
+	}
+
+	let mut game = Game::new(level);
 
     let mut mode = "3D";
+    let mut graphics = GraphicsSettings::default();
 
-    // Inicializar los reproductores de audio
-    let background_music = AudioPlayer::new("assets/death_star_alarm.mp3");
+    // Inicializar los reproductores de audio (el juego sigue sin sonido si fallan)
+    let background_music = AudioPlayer::try_new(&game.level.music)
+        .map_err(|err| eprintln!("{}", err))
+        .ok();
     
     // Reproducir música de fondo
-    background_music.play_loop();
+    if let Some(music) = &background_music {
+        music.play_loop();
+    }
 
     // Temporizador
     let mut timer = Timer::new();
-    let start_time = Instant::now();
-    let max_time: u64 = 30; // Tiempo máximo en segundos
+
+    // Simulación a 60 ticks por segundo, independiente de los FPS
+    let mut simulation = FixedTimestep::new(60.0);
+
+    // Los ticks leen la entrada acumulada, así el mouse no depende de los FPS
+    let mut tick_input = TickInput::new();
 
     while window.is_open() {
 
         timer.update();
-
-        let elapsed_time = start_time.elapsed().as_secs();
-        let time_left = max_time.saturating_sub(elapsed_time);
+        simulation.accumulate(timer.delta_time());
 
         // Escucha entradas
-        if window.is_key_down(Key::Escape) {
+        input.poll(&window);
+        tick_input.gather(&input);
+        if input.is_key_down(Key::Escape) {
             break;
         }
-        if window.is_key_down(Key::M) {
+        if input.is_key_pressed(Key::M) {
             mode = if mode == "2D" { "3D" } else { "2D" };
         }
-        process_events(&window, &mut player, &maze);
-
-        // Verifica el tiempo restante
-        let elapsed_time = start_time.elapsed().as_secs();
-        let time_left = 30u64.saturating_sub(elapsed_time);
-
-        // Verifica la condición de victoria
-        if check_win_condition(&player, &maze) {
-            // Detén la música de fondo y reproduce el sonido de victoria
-            background_music.stop();
-			show_win_screen(&mut window, &mut framebuffer);
-            let win_sound = AudioPlayer::new("assets/celebration_sound.mp3");
-            win_sound.play();
-            println!("¡Felicidades! Has ganado el juego.");
-            std::thread::sleep(Duration::from_secs(15)); // Espera 5 segundos
-            break;
+        if input.is_key_pressed(Key::F) {
+            graphics.filter = match graphics.filter {
+                TextureFilter::Nearest => TextureFilter::Bilinear,
+                TextureFilter::Bilinear => TextureFilter::Nearest,
+            };
+        }
+        if input.is_key_pressed(Key::G) {
+            graphics.mipmaps = !graphics.mipmaps;
         }
 
-        // Verificar si el tiempo se agotó
-        if time_left == 0 {
-            background_music.stop();
-			show_defeat_screen(&mut window, &mut framebuffer);
-            let lose_sound = AudioPlayer::new("assets/explosion_sound.mp3");
-            lose_sound.play();
-            println!("Tiempo agotado. Has perdido el juego.");
-            std::thread::sleep(std::time::Duration::from_secs(5));
-            break;
+        // Avanza la simulación en ticks fijos
+        while simulation.step() {
+            game.step(&tick_input, simulation.dt);
+            tick_input.consume_events();
+        }
+
+        match game.status() {
+            GameStatus::Playing => {}
+            GameStatus::Won => {
+                // Detén la música de fondo y reproduce el sonido de victoria
+                if let Some(music) = &background_music {
+                    music.stop();
+                }
+                if let Err(err) = show_win_screen(&mut window, &mut input, &mut framebuffer) {
+                    eprintln!("{}", err);
+                    show_error_screen(&mut window, &mut input, &mut framebuffer, SCREEN_ERROR, &err);
+                }
+                println!("¡Felicidades! Has ganado el juego.");
+                play_sound("assets/celebration_sound.mp3", Duration::from_secs(15));
+                break;
+            }
+            status @ (GameStatus::TimeUp | GameStatus::Defeated) => {
+                if let Some(music) = &background_music {
+                    music.stop();
+                }
+                if let Err(err) = show_defeat_screen(&mut window, &mut input, &mut framebuffer) {
+                    eprintln!("{}", err);
+                    show_error_screen(&mut window, &mut input, &mut framebuffer, SCREEN_ERROR, &err);
+                }
+                if status == GameStatus::TimeUp {
+                    println!("Tiempo agotado. Has perdido el juego.");
+                } else {
+                    println!("Te derrotaron. Has perdido el juego.");
+                }
+                play_sound("assets/explosion_sound.mp3", Duration::from_secs(5));
+                break;
+            }
         }
 
         // Limpia el framebuffer
         framebuffer.clear();
 
+        // Dibujar al jugador interpolado entre los dos últimos ticks
+        let view = game.view_player(simulation.alpha());
+        let view_enemies = game.view_enemies(simulation.alpha());
+
         // Renderiza
         if mode == "2D" {
-            render2d(&mut framebuffer, &player, &maze);
+            render2d(&mut framebuffer, &view, &game.level, &game.doors);
         } else {
-            let mut z_buffer = vec![f32::INFINITY; framebuffer.width];
-            render3d(&mut framebuffer, &player, &mut z_buffer, &maze);
-            render_enemies(&mut framebuffer, &player, &mut z_buffer);
-            render_ui(&mut framebuffer);
+            let z_buffer = render3d(&mut framebuffer, &view, &game.level, &game.doors, &game.lighting, &graphics, game.elapsed_time);
+            let mut sprites = enemy_sprites(&view_enemies, &view);
+            sprites.extend(item_sprites(&game.level.maze));
+            sprites.extend(projectile_sprites(&game.projectiles));
+            render_sprites(&mut framebuffer, &view, sprites, &z_buffer, &game.lighting, &graphics);
+            render_ui(&mut framebuffer, &view);
         }
 
 		// Agrega el renderizado del minimapa aquí
-		render_minimap(&mut framebuffer, &maze, &player);
+		render_minimap(&mut framebuffer, &game.level, &view);
 
         // Mostrar FPS y tiempo restante en la esquina superior izquierda
         window.set_title(&format!(
-            "FPS: {:.1} | Tiempo restante: {}s",
+            "{} | FPS: {:.1} | Tiempo restante: {:.0}s | {}",
+            game.level.name,
             timer.get_fps(),
-            time_left
+            game.time_left(),
+            graphics.describe()
         ));
 
         // Actualiza la ventana con el contenido del framebuffer
@@ -529,4 +563,3 @@ fn main() {
         std::thread::sleep(frame_delay);
     }
 }
-
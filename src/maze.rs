@@ -1,14 +1,322 @@
 //maze.rs
 
+use nalgebra_glm::Vec2;
+use std::collections::HashMap;
+use std::f32::consts::PI;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::path::Path;
+use crate::ai::Brain;
+use crate::enemy::{Enemy, EnemyKind};
+use crate::error::GameError;
+use crate::lighting::Fog;
+use crate::theme::{Theme, DEFAULT_THEME};
 
-pub fn load_maze(filename: &str) -> Vec<Vec<char>> {
-    let file = File::open(filename).unwrap();
+pub mod generate;
+pub mod validate;
+
+// Las secciones opcionales del archivo empiezan con una línea '#nombre'
+const FLOOR_SECTION: &str = "#floor";
+const CEILING_SECTION: &str = "#ceiling";
+const LIGHTS_SECTION: &str = "#lights";
+const REQUIRE_SECTION: &str = "#require";
+const LEVEL_SECTION: &str = "#level";
+const THEME_SECTION: &str = "#theme";
+
+// Valores por defecto cuando el nivel no los declara en #level
+const DEFAULT_SPAWN: (usize, usize) = (2, 2);
+const DEFAULT_FACING: f32 = PI / 3.0;
+const DEFAULT_TIME_LIMIT: f32 = 30.0;
+const DEFAULT_MUSIC: &str = "assets/death_star_alarm.mp3";
+
+// Texturas de piso y techo por celda; cada carácter se busca en las entradas `surface`
+// del tema ('h' hangar y 'c' corredor en el tema por defecto, ' ' color plano)
+pub struct Surfaces {
+    pub floor: Vec<Vec<char>>,
+    pub ceiling: Vec<Vec<char>>,
+}
+
+impl Surfaces {
+    pub fn floor_at(&self, col: usize, row: usize) -> char {
+        surface_at(&self.floor, col, row)
+    }
+
+    pub fn ceiling_at(&self, col: usize, row: usize) -> char {
+        surface_at(&self.ceiling, col, row)
+    }
+}
+
+fn surface_at(layer: &[Vec<char>], col: usize, row: usize) -> char {
+    layer
+        .get(row)
+        .and_then(|line| line.get(col))
+        .copied()
+        .unwrap_or(' ')
+}
+
+fn read_lines(filename: &str) -> Result<Vec<String>, GameError> {
+    let to_error = |source| GameError::Io { path: filename.to_string(), source };
+    let file = File::open(filename).map_err(to_error)?;
     let reader = BufReader::new(file);
 
-    reader
-        .lines()
-        .map(|line| line.unwrap().chars().collect())
-        .collect()
-}
\ No newline at end of file
+    reader.lines().map(|line| line.map_err(to_error)).collect()
+}
+
+// Devuelve las filas de la sección indicada (None = la cuadrícula de paredes)
+fn section(lines: &[String], name: Option<&str>) -> Vec<Vec<char>> {
+    let mut current = None;
+    let mut rows = Vec::new();
+
+    for line in lines {
+        if line.starts_with('#') {
+            current = Some(line.trim());
+            continue;
+        }
+        if current == name {
+            rows.push(line.chars().collect());
+        }
+    }
+
+    rows
+}
+
+pub fn try_load_maze(filename: &str) -> Result<Vec<Vec<char>>, GameError> {
+    Ok(section(&read_lines(filename)?, None))
+}
+
+// Nivel completo: laberinto, secciones opcionales y metadatos de #level
+pub struct Level {
+    pub name: String,
+    pub maze: Vec<Vec<char>>,
+    pub surfaces: Surfaces,
+    pub lights: Vec<Vec<char>>,        // '*' marca fuentes de luz
+    pub requirements: Vec<char>,       // objetos necesarios para que la salida 'g' cuente
+    pub spawn: (usize, usize),         // celda (columna, fila) donde aparece el jugador
+    pub enemy_spawns: Vec<(EnemyKind, (usize, usize))>, // enemigos y su celda inicial
+    pub patrols: HashMap<usize, Vec<(usize, usize)>>,    // celdas de patrulla por número de enemigo
+    pub facing: f32,                   // ángulo inicial en radianes
+    pub time_limit: f32,               // segundos
+    pub music: String,
+    pub fog: Fog,                      // color y densidad de la niebla
+    pub theme: Theme,                  // texturas, colores y solidez de cada carácter
+}
+
+impl Level {
+    // Nivel sin secciones opcionales y con los valores por defecto. No lee archivos, así que
+    // el tema queda vacío: `try_load_level` carga el tema por defecto, y los niveles armados
+    // a mano lo asignan con `Theme::load(DEFAULT_THEME)`.
+    // Los caracteres de enemigos se quitan del laberinto y quedan en `enemy_spawns`.
+    pub fn new(name: &str, mut maze: Vec<Vec<char>>) -> Self {
+        let mut enemy_spawns = Vec::new();
+        for (row, line) in maze.iter_mut().enumerate() {
+            for (col, cell) in line.iter_mut().enumerate() {
+                if let Some(kind) = EnemyKind::from_glyph(*cell) {
+                    enemy_spawns.push((kind, (col, row)));
+                    *cell = ' ';
+                }
+            }
+        }
+
+        Level {
+            name: name.to_string(),
+            maze,
+            surfaces: Surfaces { floor: Vec::new(), ceiling: Vec::new() },
+            lights: Vec::new(),
+            requirements: Vec::new(),
+            spawn: DEFAULT_SPAWN,
+            enemy_spawns,
+            patrols: HashMap::new(),
+            facing: DEFAULT_FACING,
+            time_limit: DEFAULT_TIME_LIMIT,
+            music: DEFAULT_MUSIC.to_string(),
+            fog: Fog::default(),
+            theme: Theme::empty(),
+        }
+    }
+
+    // Centro de la celda inicial en coordenadas del mundo
+    pub fn spawn_position(&self, block_size: usize) -> Vec2 {
+        Vec2::new(self.spawn.0 as f32 + 0.5, self.spawn.1 as f32 + 0.5) * block_size as f32
+    }
+
+    // Enemigos nuevos al centro de sus celdas, con la vida completa
+    pub fn spawn_enemies(&self, block_size: usize) -> Vec<Enemy> {
+        self.enemy_spawns
+            .iter()
+            .enumerate()
+            .map(|(id, &(kind, (col, row)))| {
+                let pos = Vec2::new(col as f32 + 0.5, row as f32 + 0.5) * block_size as f32;
+                let mut enemy = Enemy::new(id, kind, pos, 0.0);
+                if let Some(waypoints) = self.patrols.get(&id) {
+                    enemy.brain = Brain::new(waypoints.clone());
+                }
+                enemy
+            })
+            .collect()
+    }
+}
+
+// "east", "south", "west", "north" o un ángulo en grados (0 = este, 90 = sur)
+fn parse_facing(value: &str) -> Option<f32> {
+    match value.to_ascii_lowercase().as_str() {
+        "east" => Some(0.0),
+        "south" => Some(PI / 2.0),
+        "west" => Some(PI),
+        "north" => Some(PI * 1.5),
+        degrees => degrees.parse::<f32>().ok().map(f32::to_radians),
+    }
+}
+
+// Celda escrita como `columna fila`; None si sobra, falta o no es un número alguna parte
+fn parse_cell(text: &str) -> Option<(usize, usize)> {
+    let mut parts = text.split_whitespace().map(str::parse::<usize>);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(col)), Some(Ok(row)), None) => Some((col, row)),
+        _ => None,
+    }
+}
+
+// Aplica las líneas `clave = valor` de la sección #level
+fn parse_header(path: &str, header: &[Vec<char>], level: &mut Level) -> Result<(), GameError> {
+    for line in header {
+        let line: String = line.iter().collect();
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let invalid = || GameError::InvalidLevel {
+            path: path.to_string(),
+            message: format!("#level: linea invalida '{}'", line),
+        };
+        let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+        let (key, value) = (key.trim(), value.trim());
+
+        match key {
+            "name" => level.name = value.to_string(),
+            "spawn" => level.spawn = parse_cell(value).ok_or_else(invalid)?,
+            "facing" => level.facing = parse_facing(value).ok_or_else(invalid)?,
+            "time" => level.time_limit = value.parse().map_err(|_| invalid())?,
+            "music" => level.music = value.to_string(),
+            "fog" => {
+                // fog = <color> <densidad>
+                let [color, density] = value.split_whitespace().collect::<Vec<_>>()[..] else { return Err(invalid()) };
+                let color = u32::from_str_radix(color.trim_start_matches("0x"), 16).map_err(|_| invalid())?;
+                let density: f32 = density.parse().map_err(|_| invalid())?;
+                if !(density >= 0.0 && density.is_finite()) {
+                    return Err(invalid());
+                }
+                level.fog = Fog { color, density };
+            }
+            "theme" => level.theme.extend_from_file(value)?,
+            _ if key.starts_with("patrol ") => {
+                // patrol <enemigo> = col fila, col fila, ...
+                let id = key["patrol ".len()..].trim().parse().map_err(|_| invalid())?;
+                let waypoints = value.split(',').map(parse_cell).collect::<Option<Vec<_>>>().ok_or_else(invalid)?;
+                level.patrols.insert(id, waypoints);
+            }
+            _ => {
+                // wall <caracter> = <textura>
+                let glyph = key.strip_prefix("wall ").map(str::trim).ok_or_else(invalid)?;
+                let mut chars = glyph.chars();
+                let (Some(glyph), None) = (chars.next(), chars.next()) else { return Err(invalid()) };
+                level.theme.set_texture(glyph, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn try_load_level(path: &str) -> Result<Level, GameError> {
+    let lines = read_lines(path)?;
+    let name = Path::new(path).file_stem().map_or(path.to_string(), |stem| stem.to_string_lossy().into_owned());
+
+    let mut level = Level::new(&name, section(&lines, None));
+    level.theme = Theme::load(DEFAULT_THEME)?;
+    level.surfaces = Surfaces {
+        floor: section(&lines, Some(FLOOR_SECTION)),
+        ceiling: section(&lines, Some(CEILING_SECTION)),
+    };
+    level.lights = section(&lines, Some(LIGHTS_SECTION));
+    level.requirements = section(&lines, Some(REQUIRE_SECTION))
+        .into_iter()
+        .flatten()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    parse_header(path, &section(&lines, Some(LEVEL_SECTION)), &mut level)?;
+    for line in section(&lines, Some(THEME_SECTION)) {
+        level.theme.parse_line(path, &line.iter().collect::<String>())?;
+    }
+
+    Ok(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(lines: &[&str]) -> Result<Level, GameError> {
+        let mut level = Level::new("prueba", Vec::new());
+        let lines: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+        parse_header("prueba.txt", &lines, &mut level)?;
+        Ok(level)
+    }
+
+    fn rejects(line: &str) {
+        assert!(
+            matches!(header(&[line]), Err(GameError::InvalidLevel { .. })),
+            "se aceptó '{}'",
+            line
+        );
+    }
+
+    #[test]
+    fn valid_keys_are_applied() {
+        let level = header(&[
+            "name = Hangar",
+            "",
+            "spawn = 3 4",
+            "facing = south",
+            "time = 45.5",
+            "music = assets/otra.mp3",
+            "fog = 0x101010 0.01",
+            "patrol 1 = 2 2, 5 2 ,5 6",
+        ])
+        .unwrap();
+        assert_eq!(level.name, "Hangar");
+        assert_eq!(level.spawn, (3, 4));
+        assert_eq!(level.facing, PI / 2.0);
+        assert_eq!(level.time_limit, 45.5);
+        assert_eq!(level.music, "assets/otra.mp3");
+        assert_eq!(level.fog, Fog { color: 0x101010, density: 0.01 });
+        assert_eq!(level.patrols[&1], vec![(2, 2), (5, 2), (5, 6)]);
+    }
+
+    #[test]
+    fn facing_accepts_degrees() {
+        let level = header(&["facing = 180"]).unwrap();
+        assert!((level.facing - PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bad_values_are_rejected() {
+        rejects("spawn = 2 x 3");
+        rejects("spawn = 2");
+        rejects("spawn = 2 3 4");
+        rejects("spawn = -1 2");
+        rejects("facing = up");
+        rejects("time = pronto");
+        rejects("fog = 101010");
+        rejects("fog = 101010 -1");
+        rejects("patrol 0 = 1 1, 2 x");
+        rejects("patrol 0 = 1 1,");
+        rejects("patrol uno = 1 1");
+        rejects("wall ab = assets/wall1.jpg");
+        rejects("sin igual");
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        rejects("speed = 3");
+    }
+}
@@ -0,0 +1,317 @@
+// generate.rs
+
+use std::collections::VecDeque;
+
+use crate::player::{BLOCK_SIZE, MOVE_SPEED};
+
+// El jugador aparece en la primera celda (arriba a la izquierda): bloque (1, 1) del archivo
+pub const SPAWN_BLOCK: (usize, usize) = (1, 1);
+
+// Tiempo de un nivel aleatorio: el recorrido perfecto multiplicado por este factor, más el margen
+const TIME_FACTOR: f32 = 2.0;
+const TIME_MARGIN: f32 = 15.0; // segundos
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [
+        Algorithm::RecursiveBacktracker,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::Wilson,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::RecursiveBacktracker => "Backtracker",
+            Algorithm::Prim => "Prim",
+            Algorithm::Kruskal => "Kruskal",
+            Algorithm::Wilson => "Wilson",
+        }
+    }
+
+    // Algoritmo por su nombre, sin importar mayúsculas
+    pub fn from_name(name: &str) -> Option<Self> {
+        Algorithm::ALL.into_iter().find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+    }
+}
+
+// Generador pseudoaleatorio SplitMix64: el mismo `seed` produce el mismo laberinto
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// Cuadrícula de celdas con los pasajes abiertos entre vecinas
+struct Grid {
+    width: usize,
+    height: usize,
+    open_right: Vec<bool>,
+    open_down: Vec<bool>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Grid {
+            width,
+            height,
+            open_right: vec![false; width * height],
+            open_down: vec![false; width * height],
+        }
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let mut result = Vec::with_capacity(4);
+        if x > 0 { result.push(cell - 1); }
+        if x + 1 < self.width { result.push(cell + 1); }
+        if y > 0 { result.push(cell - self.width); }
+        if y + 1 < self.height { result.push(cell + self.width); }
+        result
+    }
+
+    fn carve(&mut self, a: usize, b: usize) {
+        let (a, b) = (a.min(b), a.max(b));
+        if b == a + 1 {
+            self.open_right[a] = true;
+        } else {
+            self.open_down[a] = true;
+        }
+    }
+
+    fn is_open(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (a.min(b), a.max(b));
+        if b == a + 1 { self.open_right[a] } else { self.open_down[a] }
+    }
+}
+
+fn recursive_backtracker(grid: &mut Grid, rng: &mut Rng) {
+    let mut visited = vec![false; grid.width * grid.height];
+    let mut stack = vec![0];
+    visited[0] = true;
+
+    while let Some(&cell) = stack.last() {
+        let unvisited: Vec<usize> = grid.neighbors(cell).into_iter().filter(|&n| !visited[n]).collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = unvisited[rng.below(unvisited.len())];
+        grid.carve(cell, next);
+        visited[next] = true;
+        stack.push(next);
+    }
+}
+
+fn prim(grid: &mut Grid, rng: &mut Rng) {
+    let mut in_maze = vec![false; grid.width * grid.height];
+    let mut frontier: Vec<(usize, usize)> = Vec::new(); // (celda en el laberinto, vecina fuera)
+    in_maze[0] = true;
+    frontier.extend(grid.neighbors(0).into_iter().map(|n| (0, n)));
+
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.below(frontier.len()));
+        if in_maze[to] {
+            continue;
+        }
+        grid.carve(from, to);
+        in_maze[to] = true;
+        frontier.extend(grid.neighbors(to).into_iter().filter(|&n| !in_maze[n]).map(|n| (to, n)));
+    }
+}
+
+fn kruskal(grid: &mut Grid, rng: &mut Rng) {
+    fn find(parent: &mut [usize], cell: usize) -> usize {
+        let mut root = cell;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        let mut current = cell;
+        while parent[current] != root {
+            let next = parent[current];
+            parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    let mut parent: Vec<usize> = (0..grid.width * grid.height).collect();
+    let mut edges: Vec<(usize, usize)> = (0..grid.width * grid.height)
+        .flat_map(|cell| grid.neighbors(cell).into_iter().filter(move |&n| n > cell).map(move |n| (cell, n)))
+        .collect();
+    rng.shuffle(&mut edges);
+
+    for (a, b) in edges {
+        let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+        if root_a != root_b {
+            parent[root_a] = root_b;
+            grid.carve(a, b);
+        }
+    }
+}
+
+fn wilson(grid: &mut Grid, rng: &mut Rng) {
+    let total = grid.width * grid.height;
+    let mut in_maze = vec![false; total];
+    in_maze[0] = true;
+    let mut next_step = vec![0; total];
+
+    for start in 0..total {
+        if in_maze[start] {
+            continue;
+        }
+        // Caminata aleatoria sin lazos: solo se recuerda la última salida de cada celda
+        let mut cell = start;
+        while !in_maze[cell] {
+            let neighbors = grid.neighbors(cell);
+            next_step[cell] = neighbors[rng.below(neighbors.len())];
+            cell = next_step[cell];
+        }
+        // Agregar el camino al laberinto
+        let mut cell = start;
+        while !in_maze[cell] {
+            in_maze[cell] = true;
+            grid.carve(cell, next_step[cell]);
+            cell = next_step[cell];
+        }
+    }
+}
+
+// Celda más lejana al inicio, donde se coloca la salida
+fn farthest_cell(grid: &Grid) -> usize {
+    let mut distance = vec![usize::MAX; grid.width * grid.height];
+    let mut queue = VecDeque::from([0]);
+    distance[0] = 0;
+    let mut farthest = 0;
+
+    while let Some(cell) = queue.pop_front() {
+        if distance[cell] > distance[farthest] {
+            farthest = cell;
+        }
+        for n in grid.neighbors(cell) {
+            if distance[n] == usize::MAX && grid.is_open(cell, n) {
+                distance[n] = distance[cell] + 1;
+                queue.push_back(n);
+            }
+        }
+    }
+    farthest
+}
+
+// Convierte la cuadrícula al formato de texto de los niveles (`+--+` y `|`)
+fn to_ascii(grid: &Grid, exit: usize) -> Vec<Vec<char>> {
+    let mut rows = Vec::with_capacity(grid.height * 2 + 1);
+
+    for y in 0..grid.height {
+        let mut top = String::new();
+        let mut middle = String::new();
+        for x in 0..grid.width {
+            let cell = y * grid.width + x;
+            let open_up = y > 0 && grid.open_down[cell - grid.width];
+            let open_left = x > 0 && grid.open_right[cell - 1];
+            top.push_str(if open_up { "+  " } else { "+--" });
+            middle.push(if open_left { ' ' } else { '|' });
+            middle.push_str(if cell == exit { " g" } else { "  " });
+        }
+        top.push('+');
+        middle.push('|');
+        rows.push(top.chars().collect());
+        rows.push(middle.chars().collect());
+    }
+    rows.push("+--".repeat(grid.width).chars().chain(std::iter::once('+')).collect());
+
+    rows
+}
+
+// Genera un laberinto perfecto de `width` x `height` celdas en el formato de los niveles
+pub fn generate(width: usize, height: usize, algorithm: Algorithm, seed: u64) -> Vec<Vec<char>> {
+    let mut grid = Grid::new(width.max(2), height.max(2));
+    let mut rng = Rng(seed);
+
+    match algorithm {
+        Algorithm::RecursiveBacktracker => recursive_backtracker(&mut grid, &mut rng),
+        Algorithm::Prim => prim(&mut grid, &mut rng),
+        Algorithm::Kruskal => kruskal(&mut grid, &mut rng),
+        Algorithm::Wilson => wilson(&mut grid, &mut rng),
+    }
+
+    let exit = farthest_cell(&grid);
+    to_ascii(&grid, exit)
+}
+
+// Segundos para terminar un laberinto generado: lo que tarda el jugador en recorrer el
+// camino más corto de `spawn` a la salida, con holgura para explorar
+pub fn time_limit(maze: &[Vec<char>], spawn: (usize, usize)) -> f32 {
+    let mut distance: Vec<Vec<Option<usize>>> = maze.iter().map(|line| vec![None; line.len()]).collect();
+    let mut queue = VecDeque::from([spawn]);
+    distance[spawn.1][spawn.0] = Some(0);
+    let mut blocks = 0;
+
+    while let Some((col, row)) = queue.pop_front() {
+        let steps = distance[row][col].unwrap_or(0);
+        if maze[row][col] == 'g' {
+            blocks = steps;
+            break;
+        }
+        for (n_col, n_row) in [(col.wrapping_sub(1), row), (col + 1, row), (col, row.wrapping_sub(1)), (col, row + 1)] {
+            let Some(&cell) = maze.get(n_row).and_then(|line| line.get(n_col)) else { continue };
+            if (cell == ' ' || cell == 'g') && distance[n_row][n_col].is_none() {
+                distance[n_row][n_col] = Some(steps + 1);
+                queue.push_back((n_col, n_row));
+            }
+        }
+    }
+
+    blocks as f32 * BLOCK_SIZE / MOVE_SPEED * TIME_FACTOR + TIME_MARGIN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::validate::validate;
+    use crate::theme::{Theme, DEFAULT_THEME};
+
+    #[test]
+    fn the_same_seed_gives_the_same_maze() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(generate(12, 9, algorithm, 42), generate(12, 9, algorithm, 42), "{}", algorithm.name());
+            assert_ne!(generate(12, 9, algorithm, 42), generate(12, 9, algorithm, 43), "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn every_algorithm_makes_a_valid_level() {
+        let theme = Theme::load(DEFAULT_THEME).unwrap();
+        for algorithm in Algorithm::ALL {
+            for seed in 0..5 {
+                let maze = generate(10, 7, algorithm, seed);
+                let diagnostics = validate(&maze, SPAWN_BLOCK, &theme, &[]);
+                assert!(diagnostics.is_empty(), "{} semilla {}: {:?}", algorithm.name(), seed, diagnostics);
+                assert!(time_limit(&maze, SPAWN_BLOCK) > TIME_MARGIN);
+            }
+        }
+    }
+}
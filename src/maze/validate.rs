@@ -0,0 +1,295 @@
+// validate.rs
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::door::is_door;
+use crate::error::GameError;
+use crate::item::Keycard;
+use crate::maze::Level;
+use crate::theme::Theme;
+
+// Caracteres que el juego entiende aunque no estén en el tema
+const BUILTIN_GLYPHS: &[char] = &[' ', 'r', 'b', 'y'];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,   // El nivel no se puede jugar
+    Warning, // El nivel se puede jugar, pero algo está mal
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    EmptyMaze,
+    RaggedRow { row: usize, len: usize, expected: usize },
+    UnknownGlyph { col: usize, row: usize, glyph: char },
+    OpenBorder { col: usize, row: usize },
+    MissingGoal,
+    SpawnBlocked { col: usize, row: usize },
+    UnreachableGoal,
+    MissingItem { glyph: char },     // #require pide una tarjeta que no está en el laberinto
+    UnreachableItem { glyph: char }, // ... o que no se puede alcanzar desde el inicio
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnostic::RaggedRow { .. } | Diagnostic::UnknownGlyph { .. } | Diagnostic::OpenBorder { .. } => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::EmptyMaze => write!(f, "el laberinto esta vacio"),
+            Diagnostic::RaggedRow { row, len, expected } => {
+                write!(f, "fila {}: tiene {} columnas, se esperaban {}", row, len, expected)
+            }
+            Diagnostic::UnknownGlyph { col, row, glyph } => {
+                write!(f, "fila {}, columna {}: caracter desconocido {:?}", row, col, glyph)
+            }
+            Diagnostic::OpenBorder { col, row } => {
+                write!(f, "fila {}, columna {}: borde abierto, los rayos pueden salir del mapa", row, col)
+            }
+            Diagnostic::MissingGoal => write!(f, "no hay salida 'g'"),
+            Diagnostic::SpawnBlocked { col, row } => {
+                write!(f, "fila {}, columna {}: el jugador aparece dentro de una pared", row, col)
+            }
+            Diagnostic::UnreachableGoal => write!(f, "la salida 'g' no se puede alcanzar desde el inicio"),
+            Diagnostic::MissingItem { glyph } => {
+                write!(f, "#require pide la tarjeta {:?}, pero no esta en el laberinto", glyph)
+            }
+            Diagnostic::UnreachableItem { glyph } => {
+                write!(f, "la tarjeta {:?} que pide #require no se puede alcanzar desde el inicio", glyph)
+            }
+        }
+    }
+}
+
+fn cell_at(maze: &[Vec<char>], col: usize, row: usize) -> Option<char> {
+    maze.get(row).and_then(|line| line.get(col)).copied()
+}
+
+// Celdas por las que se puede caminar, suponiendo que las puertas se abren
+// y que las barreras se abren si ya se tiene su tarjeta; el resto lo decide el tema
+fn is_walkable(cell: char, theme: &Theme, keys: &[Keycard]) -> bool {
+    match cell {
+        ' ' => true,
+        _ if is_door(cell) || Keycard::from_pickup(cell).is_some() => true,
+        _ => match Keycard::from_barrier(cell) {
+            Some(key) => keys.contains(&key),
+            None => !theme.is_solid(cell),
+        },
+    }
+}
+
+// Celdas que los rayos atraviesan (ver `trace_ray`); las puertas se dibujan aunque estén abiertas
+fn lets_rays_through(cell: char) -> bool {
+    cell == ' ' || Keycard::from_pickup(cell).is_some()
+}
+
+// Lo que se alcanza desde el inicio: las tarjetas y si se llega a la salida
+struct Reach {
+    keys: Vec<Keycard>,
+    goal: bool,
+}
+
+// BFS desde el inicio; repite mientras se encuentren tarjetas nuevas que abran barreras
+fn explore(maze: &[Vec<char>], spawn: (usize, usize), theme: &Theme) -> Reach {
+    let mut keys: Vec<Keycard> = Vec::new();
+
+    loop {
+        let mut visited: Vec<Vec<bool>> = maze.iter().map(|line| vec![false; line.len()]).collect();
+        let mut queue = VecDeque::from([spawn]);
+        visited[spawn.1][spawn.0] = true;
+        let mut found_keys = keys.clone();
+        let mut goal = false;
+
+        while let Some((col, row)) = queue.pop_front() {
+            let cell = maze[row][col];
+            goal |= cell == 'g';
+            if let Some(key) = Keycard::from_pickup(cell) {
+                if !found_keys.contains(&key) {
+                    found_keys.push(key);
+                }
+            }
+
+            let neighbors = [
+                (col.wrapping_sub(1), row),
+                (col + 1, row),
+                (col, row.wrapping_sub(1)),
+                (col, row + 1),
+            ];
+            for (n_col, n_row) in neighbors {
+                let Some(n_cell) = cell_at(maze, n_col, n_row) else { continue };
+                if !visited[n_row][n_col] && is_walkable(n_cell, theme, &keys) {
+                    visited[n_row][n_col] = true;
+                    queue.push_back((n_col, n_row));
+                }
+            }
+        }
+
+        if found_keys.len() == keys.len() {
+            return Reach { keys, goal };
+        }
+        keys = found_keys;
+    }
+}
+
+// Revisa el laberinto y devuelve todos los problemas encontrados.
+// `spawn` es la celda (columna, fila) donde aparece el jugador, `theme`
+// indica qué caracteres existen y cuáles bloquean el paso, y `required` son
+// los objetos de #require (el juego ignora los que no son tarjetas).
+pub fn validate(maze: &[Vec<char>], spawn: (usize, usize), theme: &Theme, required: &[char]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let Some(expected) = maze.first().map(|line| line.len()).filter(|&len| len > 0) else {
+        diagnostics.push(Diagnostic::EmptyMaze);
+        return diagnostics;
+    };
+
+    for (row, line) in maze.iter().enumerate() {
+        if line.len() != expected {
+            diagnostics.push(Diagnostic::RaggedRow { row, len: line.len(), expected });
+        }
+        for (col, &glyph) in line.iter().enumerate() {
+            if !BUILTIN_GLYPHS.contains(&glyph) && !theme.contains(glyph) {
+                diagnostics.push(Diagnostic::UnknownGlyph { col, row, glyph });
+            }
+            // Una celda abierta en el borde (o junto a una fila más corta) deja escapar los rayos
+            let on_border = row == 0
+                || row + 1 == maze.len()
+                || col == 0
+                || col + 1 == line.len()
+                || cell_at(maze, col, row - 1).is_none()
+                || cell_at(maze, col, row + 1).is_none();
+            if on_border && lets_rays_through(glyph) {
+                diagnostics.push(Diagnostic::OpenBorder { col, row });
+            }
+        }
+    }
+
+    if !maze.iter().flatten().any(|&cell| cell == 'g') {
+        diagnostics.push(Diagnostic::MissingGoal);
+        return diagnostics;
+    }
+
+    for &glyph in required.iter().filter(|&&glyph| Keycard::from_pickup(glyph).is_some()) {
+        if !maze.iter().flatten().any(|&cell| cell == glyph) {
+            diagnostics.push(Diagnostic::MissingItem { glyph });
+        }
+    }
+
+    // Las puertas empiezan cerradas, así que tampoco se puede aparecer en una
+    match cell_at(maze, spawn.0, spawn.1) {
+        Some(cell) if is_walkable(cell, theme, &[]) && !is_door(cell) => {
+            let reach = explore(maze, spawn, theme);
+            if !reach.goal {
+                diagnostics.push(Diagnostic::UnreachableGoal);
+            }
+            for &glyph in required {
+                let Some(key) = Keycard::from_pickup(glyph) else { continue };
+                let missing = diagnostics.contains(&Diagnostic::MissingItem { glyph });
+                if !missing && !reach.keys.contains(&key) {
+                    diagnostics.push(Diagnostic::UnreachableItem { glyph });
+                }
+            }
+        }
+        _ => diagnostics.push(Diagnostic::SpawnBlocked { col: spawn.0, row: spawn.1 }),
+    }
+
+    diagnostics
+}
+
+// Valida un nivel cargado: devuelve las advertencias, o un error con todos los
+// problemas que impiden jugarlo
+pub fn check(level: &Level) -> Result<Vec<Diagnostic>, GameError> {
+    let (errors, warnings): (Vec<Diagnostic>, Vec<Diagnostic>) =
+        validate(&level.maze, level.spawn, &level.theme, &level.requirements)
+            .into_iter()
+            .partition(|d| d.severity() == Severity::Error);
+
+    if errors.is_empty() {
+        Ok(warnings)
+    } else {
+        let message = errors.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("; ");
+        Err(GameError::InvalidLevel { path: level.name.clone(), message })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::DEFAULT_THEME;
+
+    fn maze(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|row| row.chars().collect()).collect()
+    }
+
+    #[test]
+    fn ragged_rows_are_reported() {
+        let maze = maze(&["+---+", "|  g|", "| |", "+---+"]);
+        let diagnostics = validate(&maze, (1, 1), &Theme::load(DEFAULT_THEME).unwrap(), &[]);
+        assert!(diagnostics.contains(&Diagnostic::RaggedRow { row: 2, len: 3, expected: 5 }));
+    }
+
+    #[test]
+    fn open_border_is_reported() {
+        let maze = maze(&["+- -+", "|  g|", "+---+"]);
+        let diagnostics = validate(&maze, (1, 1), &Theme::load(DEFAULT_THEME).unwrap(), &[]);
+        assert_eq!(diagnostics, vec![Diagnostic::OpenBorder { col: 2, row: 0 }]);
+    }
+
+    #[test]
+    fn goal_on_the_border_stops_rays() {
+        let maze = maze(&["+---+", "|   g", "+---+"]);
+        assert!(validate(&maze, (1, 1), &Theme::load(DEFAULT_THEME).unwrap(), &[]).is_empty());
+    }
+
+    #[test]
+    fn goal_behind_a_barrier_needs_its_keycard() {
+        let theme = Theme::load(DEFAULT_THEME).unwrap();
+        let with_key = maze(&["+-----+", "|r  Rg|", "+-----+"]);
+        assert!(explore(&with_key, (2, 1), &theme).goal);
+
+        let without_key = maze(&["+-----+", "|   Rg|", "+-----+"]);
+        assert!(!explore(&without_key, (1, 1), &theme).goal);
+        assert_eq!(validate(&without_key, (1, 1), &theme, &[]), vec![Diagnostic::UnreachableGoal]);
+    }
+
+    #[test]
+    fn required_keycards_must_exist_and_be_reachable() {
+        let theme = Theme::load(DEFAULT_THEME).unwrap();
+        let maze = maze(&["+------+", "|r  g|b|", "|    |R|", "+------+"]);
+        assert!(validate(&maze, (1, 1), &theme, &['r']).is_empty());
+        assert_eq!(validate(&maze, (1, 1), &theme, &['y']), vec![Diagnostic::MissingItem { glyph: 'y' }]);
+        assert_eq!(validate(&maze, (1, 1), &theme, &['b']), vec![Diagnostic::UnreachableItem { glyph: 'b' }]);
+        // El juego ignora lo que no es una tarjeta
+        assert!(validate(&maze, (1, 1), &theme, &['?']).is_empty());
+    }
+
+    #[test]
+    fn spawning_on_a_closed_door_is_blocked() {
+        let maze = maze(&["+---+", "|D g|", "+---+"]);
+        let diagnostics = validate(&maze, (1, 1), &Theme::load(DEFAULT_THEME).unwrap(), &[]);
+        assert_eq!(diagnostics, vec![Diagnostic::SpawnBlocked { col: 1, row: 1 }]);
+    }
+
+    #[test]
+    fn check_splits_warnings_from_errors() {
+        let theme = Theme::load(DEFAULT_THEME).unwrap();
+        let mut level = Level::new("prueba", maze(&["+- -+", "|  g|", "+---+"]));
+        level.spawn = (1, 1);
+        level.theme = theme;
+        assert_eq!(check(&level).unwrap(), vec![Diagnostic::OpenBorder { col: 2, row: 0 }]);
+
+        level.requirements = vec!['r'];
+        let Err(GameError::InvalidLevel { path, message }) = check(&level) else { panic!("se esperaba un error") };
+        assert_eq!(path, "prueba");
+        assert_eq!(message, Diagnostic::MissingItem { glyph: 'r' }.to_string());
+    }
+}
@@ -1,83 +1,193 @@
 // player.rs
 use nalgebra_glm::Vec2;
-use minifb::{Key, Window};
+use minifb::Key;
 use std::f32::consts::PI;
+use crate::door::{is_door, Doors};
+use crate::enemy::{Enemy, ENEMY_RADIUS};
+use crate::item::{is_open_barrier, Keycard};
+use crate::input::InputSource;
+use crate::game_loop::{lerp_angle, lerp_vec2};
+use crate::maze::Level;
+use crate::theme::Theme;
 
+#[derive(Clone)]
 pub struct Player {
     pub pos: Vec2,
     pub a: f32, // ángulo de visión
     pub fov: f32, // campo de visión
-    pub last_mouse_x: Option<f32>, // para rastrear la última posición del mouse
+    pub inventory: Vec<Keycard>, // tarjetas de acceso recogidas
+    pub radius: f32, // radio del círculo de colisión
+    pub health: f32,
+    pub armor: f32, // absorbe parte del daño mientras dure
+    pub ammo: u32,
+    pub weapon_cooldown: f32, // segundos que faltan para poder disparar otra vez
 }
 
-// Tamaño del bloque (debe coincidir con el bloque en `main.rs`)
-const BLOCK_SIZE: f32 = 70.0;
+const MAX_HEALTH: f32 = 100.0;
+const STARTING_ARMOR: f32 = 50.0;
+const STARTING_AMMO: u32 = 24;
+const ARMOR_ABSORPTION: f32 = 0.6; // Fracción del daño que recibe la armadura
 
-// Verifica si la nueva posición del jugador colisionará con una pared
-pub fn is_collision(x: f32, y: f32, maze: &Vec<Vec<char>>, block_size: usize) -> bool {
-    let maze_x = (x / block_size as f32) as isize;
-    let maze_y = (y / block_size as f32) as isize;
+impl Player {
+    pub fn new(pos: Vec2, a: f32) -> Self {
+        Player {
+            pos,
+            a,
+            fov: PI / 3.0,
+            inventory: Vec::new(),
+            radius: 14.0,
+            health: MAX_HEALTH,
+            armor: STARTING_ARMOR,
+            ammo: STARTING_AMMO,
+            weapon_cooldown: 0.0,
+        }
+    }
 
-    // Verificar si los índices están dentro de los límites del laberinto
-    if maze_x < 0 || maze_y < 0 || maze_y as usize >= maze.len() || maze_x as usize >= maze[0].len() {
+    pub fn is_alive(&self) -> bool {
+        self.health > 0.0
+    }
+
+    // Resta vida; la armadura absorbe parte del daño hasta agotarse
+    pub fn take_damage(&mut self, amount: f32) {
+        let absorbed = (amount * ARMOR_ABSORPTION).min(self.armor);
+        self.armor -= absorbed;
+        self.health = (self.health - (amount - absorbed)).max(0.0);
+    }
+
+    // Estado para dibujar entre el tick anterior (`previous`) y el actual
+    pub fn interpolated(&self, previous: &Player, alpha: f32) -> Player {
+        Player {
+            pos: lerp_vec2(previous.pos, self.pos, alpha),
+            a: lerp_angle(previous.a, self.a, alpha),
+            ..self.clone()
+        }
+    }
+}
+
+// Tamaño del bloque (debe coincidir con el bloque en `render.rs`)
+pub const BLOCK_SIZE: f32 = 70.0;
+pub const MOVE_SPEED: f32 = 90.0; // Píxeles por segundo
+
+// Verifica si la celda (col, row) bloquea el paso; fuera del laberinto cuenta como pared
+pub fn is_solid(col: isize, row: isize, maze: &[Vec<char>], theme: &Theme, doors: &Doors, inventory: &[Keycard]) -> bool {
+    if col < 0 || row < 0 {
+        return true;
+    }
+    let Some(&cell) = maze.get(row as usize).and_then(|line| line.get(col as usize)) else {
         return true; // Considerar fuera de los límites como una colisión
+    };
+
+    if is_door(cell) {
+        return !doors.is_passable(col as usize, row as usize); // Puertas sólidas mientras no estén abiertas
+    }
+    if is_open_barrier(cell, inventory) || Keycard::from_pickup(cell).is_some() {
+        return false; // Barreras abiertas y objetos no bloquean el paso
+    }
+    cell != ' ' && theme.is_solid(cell) // El tema decide qué paredes bloquean (por ejemplo, 'g' no)
+}
+
+// Verifica si el punto (x, y) está dentro de una pared
+pub fn is_collision(x: f32, y: f32, maze: &[Vec<char>], theme: &Theme, doors: &Doors, inventory: &[Keycard], block_size: usize) -> bool {
+    let block = block_size as f32;
+    is_solid((x / block).floor() as isize, (y / block).floor() as isize, maze, theme, doors, inventory)
+}
+
+// Verifica si un círculo de radio `radius` centrado en `pos` toca alguna pared
+pub fn is_circle_collision(pos: Vec2, radius: f32, maze: &[Vec<char>], theme: &Theme, doors: &Doors, inventory: &[Keycard], block_size: usize) -> bool {
+    let block = block_size as f32;
+    let min_col = ((pos.x - radius) / block).floor() as isize;
+    let max_col = ((pos.x + radius) / block).floor() as isize;
+    let min_row = ((pos.y - radius) / block).floor() as isize;
+    let max_row = ((pos.y + radius) / block).floor() as isize;
+
+    for row in min_row..=max_row {
+        for col in min_col..=max_col {
+            if !is_solid(col, row, maze, theme, doors, inventory) {
+                continue;
+            }
+            // Punto de la celda más cercano al centro del círculo
+            let closest_x = pos.x.clamp(col as f32 * block, (col + 1) as f32 * block);
+            let closest_y = pos.y.clamp(row as f32 * block, (row + 1) as f32 * block);
+            if (pos.x - closest_x).powi(2) + (pos.y - closest_y).powi(2) < radius * radius {
+                return true;
+            }
+        }
     }
+    false
+}
 
-    let cell = maze[maze_y as usize][maze_x as usize];
-    cell != ' ' && cell != 'g' // Ignorar 'g' como colisión
+// Mueve un círculo resolviendo cada eje por separado, para que se deslice a lo largo de las paredes.
+// También sirve para el movimiento de los enemigos.
+pub fn move_and_slide(
+    pos: Vec2,
+    delta: Vec2,
+    radius: f32,
+    level: &Level,
+    doors: &Doors,
+    inventory: &[Keycard],
+    block_size: usize,
+) -> Vec2 {
+    let (maze, theme) = (&level.maze, &level.theme);
+    let mut new_pos = pos;
+
+    let moved_x = Vec2::new(new_pos.x + delta.x, new_pos.y);
+    if !is_circle_collision(moved_x, radius, maze, theme, doors, inventory, block_size) {
+        new_pos = moved_x;
+    }
+
+    let moved_y = Vec2::new(new_pos.x, new_pos.y + delta.y);
+    if !is_circle_collision(moved_y, radius, maze, theme, doors, inventory, block_size) {
+        new_pos = moved_y;
+    }
+
+    new_pos
 }
 
 
-pub fn process_events(window: &Window, player: &mut Player, maze: &Vec<Vec<char>>) {
-    const MOVE_SPEED: f32 = 0.75;
-    const ROTATION_SPEED: f32 = PI / 80.0;
-    const MOUSE_SENSITIVITY: f32 = 0.005; // Ajusta la sensibilidad del ratón
+pub fn process_events(input: &dyn InputSource, player: &mut Player, level: &Level, doors: &Doors, dt: f32) {
+    const ROTATION_SPEED: f32 = PI; // Radianes por segundo
+    const MOUSE_SENSITIVITY: f32 = 0.005; // Radianes por píxel; cada movimiento del mouse llega a un solo tick
+    const MAX_DELTA: f32 = 0.1; // Evita saltos a través de paredes en cuadros muy lentos
+
+    let dt = dt.min(MAX_DELTA);
+    let step = MOVE_SPEED * dt;
 
-    let mut new_pos = player.pos;
+    let mut delta = Vec2::new(0.0, 0.0);
 
     // Movimiento hacia adelante (W) y hacia atrás (S)
-    if window.is_key_down(Key::W) {
-        new_pos.x += MOVE_SPEED * player.a.cos();
-        new_pos.y += MOVE_SPEED * player.a.sin();
+    if input.is_key_down(Key::W) {
+        delta.x += step * player.a.cos();
+        delta.y += step * player.a.sin();
     }
-    if window.is_key_down(Key::S) {
-        new_pos.x -= MOVE_SPEED * player.a.cos();
-        new_pos.y -= MOVE_SPEED * player.a.sin();
+    if input.is_key_down(Key::S) {
+        delta.x -= step * player.a.cos();
+        delta.y -= step * player.a.sin();
     }
 
     // Movimiento lateral (A y D)
-    if window.is_key_down(Key::A) {
-        new_pos.x -= MOVE_SPEED * (player.a + PI / 2.0).cos();
-        new_pos.y -= MOVE_SPEED * (player.a + PI / 2.0).sin();
+    if input.is_key_down(Key::A) {
+        delta.x -= step * (player.a + PI / 2.0).cos();
+        delta.y -= step * (player.a + PI / 2.0).sin();
     }
-    if window.is_key_down(Key::D) {
-        new_pos.x += MOVE_SPEED * (player.a + PI / 2.0).cos();
-        new_pos.y += MOVE_SPEED * (player.a + PI / 2.0).sin();
+    if input.is_key_down(Key::D) {
+        delta.x += step * (player.a + PI / 2.0).cos();
+        delta.y += step * (player.a + PI / 2.0).sin();
     }
 
-    // Verifica si la nueva posición es válida (sin colisión)
-    if !is_collision(new_pos.x, new_pos.y, maze, BLOCK_SIZE as usize) {
-        player.pos = new_pos;
-    }
+    // Mover resolviendo colisiones por eje (se desliza al empujar contra una pared)
+    player.pos = move_and_slide(player.pos, delta, player.radius, level, doors, &player.inventory, BLOCK_SIZE as usize);
 
     // Rotación con flechas
-    if window.is_key_down(Key::Right) {
-        player.a += ROTATION_SPEED;
+    if input.is_key_down(Key::Right) {
+        player.a += ROTATION_SPEED * dt;
     }
-    if window.is_key_down(Key::Left) {
-        player.a -= ROTATION_SPEED;
+    if input.is_key_down(Key::Left) {
+        player.a -= ROTATION_SPEED * dt;
     }
 
     // Rotación con el ratón
-    if let Some((mouse_x, _)) = window.get_mouse_pos(minifb::MouseMode::Discard) {
-        if let Some(last_mouse_x) = player.last_mouse_x {
-            let delta_x = mouse_x - last_mouse_x;
-            player.a += delta_x * MOUSE_SENSITIVITY;
-        }
-        player.last_mouse_x = Some(mouse_x);
-    } else {
-        player.last_mouse_x = None;
-    }
+    let (delta_x, _) = input.mouse_delta();
+    player.a += delta_x * MOUSE_SENSITIVITY;
 
     // Limitar el ángulo entre 0 y 2PI para evitar desbordamientos
     if player.a < 0.0 {
@@ -87,7 +197,56 @@ pub fn process_events(window: &Window, player: &mut Player, maze: &Vec<Vec<char>
     }
 }
 
-pub fn check_win_condition(player: &Player, maze: &Vec<Vec<char>>) -> bool {
+// Abre o cierra la puerta a un bloque de distancia en la dirección de la mirada
+pub fn interact(player: &Player, maze: &[Vec<char>], doors: &mut Doors, enemies: &[Enemy]) {
+    let target = player.pos + Vec2::new(player.a.cos(), player.a.sin()) * BLOCK_SIZE;
+    if target.x < 0.0 || target.y < 0.0 {
+        return;
+    }
+
+    let col = (target.x / BLOCK_SIZE) as usize;
+    let row = (target.y / BLOCK_SIZE) as usize;
+
+    // No cerrar la puerta sobre el jugador ni sobre un enemigo (ni sobre parte de su
+    // círculo de colisión): quedarían atrapados dentro de la pared
+    if circle_overlaps_cell(player.pos, player.radius, col, row)
+        || enemies
+            .iter()
+            .filter(|enemy| enemy.is_alive())
+            .any(|enemy| circle_overlaps_cell(enemy.pos, ENEMY_RADIUS, col, row))
+    {
+        return;
+    }
+
+    if let Some(&cell) = maze.get(row).and_then(|line| line.get(col)) {
+        if is_door(cell) {
+            doors.toggle(col, row);
+        }
+    }
+}
+
+// Verdadero si el círculo toca la celda (col, row)
+fn circle_overlaps_cell(center: Vec2, radius: f32, col: usize, row: usize) -> bool {
+    let closest_x = center.x.clamp(col as f32 * BLOCK_SIZE, (col + 1) as f32 * BLOCK_SIZE);
+    let closest_y = center.y.clamp(row as f32 * BLOCK_SIZE, (row + 1) as f32 * BLOCK_SIZE);
+    (center.x - closest_x).powi(2) + (center.y - closest_y).powi(2) < radius.powi(2)
+}
+
+// Recoge el objeto de la celda donde está el jugador y lo quita del laberinto
+pub fn pick_up_items(player: &mut Player, maze: &mut [Vec<char>]) {
+    let player_row = (player.pos.y / BLOCK_SIZE) as usize;
+    let player_col = (player.pos.x / BLOCK_SIZE) as usize;
+
+    if let Some(cell) = maze.get_mut(player_row).and_then(|line| line.get_mut(player_col)) {
+        if let Some(key) = Keycard::from_pickup(*cell) {
+            player.inventory.push(key);
+            *cell = ' ';
+        }
+    }
+}
+
+// `required` lista las tarjetas que hay que tener para que la salida cuente
+pub fn check_win_condition(player: &Player, maze: &[Vec<char>], required: &[Keycard]) -> bool {
     let player_row = (player.pos.y / BLOCK_SIZE) as usize;
     let player_col = (player.pos.x / BLOCK_SIZE) as usize;
 
@@ -95,5 +254,76 @@ pub fn check_win_condition(player: &Player, maze: &Vec<Vec<char>>) -> bool {
         return false; // El jugador está fuera de los límites
     }
 
+    if !required.iter().all(|key| player.inventory.contains(key)) {
+        return false; // Faltan objetos para poder salir
+    }
+
     maze[player_row][player_col] == 'g' // Verifica si la celda actual es 'g'
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enemy::EnemyKind;
+
+    // El jugador está en la celda (1, 1) mirando al este, hacia la puerta en (2, 1)
+    fn setup() -> (Vec<Vec<char>>, Doors, Player) {
+        let maze: Vec<Vec<char>> = ["#####", "# d #", "#####"].iter().map(|row| row.chars().collect()).collect();
+        let doors = Doors::from_maze(&maze);
+        let player = Player::new(Vec2::new(1.5, 1.5) * BLOCK_SIZE, 0.0);
+        (maze, doors, player)
+    }
+
+    fn open(doors: &mut Doors) {
+        doors.toggle(2, 1);
+        doors.update(10.0);
+    }
+
+    #[test]
+    fn interact_opens_and_closes_the_door_in_front() {
+        let (maze, mut doors, player) = setup();
+        interact(&player, &maze, &mut doors, &[]);
+        doors.update(10.0);
+        assert!(doors.is_passable(2, 1));
+
+        interact(&player, &maze, &mut doors, &[]);
+        doors.update(10.0);
+        assert_eq!(doors.openness(2, 1), 0.0);
+    }
+
+    #[test]
+    fn interact_ignores_cells_without_a_door() {
+        let (maze, mut doors, mut player) = setup();
+        player.a = PI; // mirando la pared del oeste
+        interact(&player, &maze, &mut doors, &[]);
+        doors.update(10.0);
+        assert_eq!(doors.openness(2, 1), 0.0);
+    }
+
+    #[test]
+    fn door_does_not_close_on_the_player() {
+        let (maze, mut doors, mut player) = setup();
+        open(&mut doors);
+        // Parte del círculo del jugador ya está dentro de la celda de la puerta
+        player.pos.x = 2.0 * BLOCK_SIZE - player.radius / 2.0;
+        interact(&player, &maze, &mut doors, &[]);
+        doors.update(10.0);
+        assert!(doors.is_passable(2, 1));
+    }
+
+    #[test]
+    fn door_does_not_close_on_a_living_enemy() {
+        let (maze, mut doors, player) = setup();
+        open(&mut doors);
+        let mut enemy = Enemy::new(0, EnemyKind::Trooper, Vec2::new(2.5, 1.5) * BLOCK_SIZE, PI);
+        interact(&player, &maze, &mut doors, std::slice::from_ref(&enemy));
+        doors.update(10.0);
+        assert!(doors.is_passable(2, 1));
+
+        // Un enemigo muerto no la detiene
+        enemy.health = 0.0;
+        interact(&player, &maze, &mut doors, &[enemy]);
+        doors.update(10.0);
+        assert_eq!(doors.openness(2, 1), 0.0);
+    }
+}
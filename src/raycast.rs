@@ -1,55 +1,306 @@
 // raycast.rs
 
+use nalgebra_glm::Vec2;
 use crate::framebuffer::Framebuffer;
 use crate::player::Player;
+use crate::door::{is_door, Doors};
+use crate::item::{is_open_barrier, Keycard};
+
+// Cara de la celda contra la que chocó el rayo
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Face {
+    North,
+    South,
+    East,
+    West,
+}
 
 pub struct Intersect {
     pub distance: f32,
     pub impact: char,
-    pub tx: usize,
+    pub u: f32,              // coordenada horizontal de textura, de 0 a 1 a lo ancho de la cara
+    pub hit: Vec2,           // punto exacto de impacto en coordenadas del mundo
+    pub cell: (usize, usize), // (columna, fila) de la celda golpeada
+    pub face: Face,
+}
+
+// Devuelve la celda en (i, j) o None si está fuera del laberinto
+fn cell_at(maze: &[Vec<char>], i: isize, j: isize) -> Option<char> {
+    if i < 0 || j < 0 {
+        return None;
+    }
+    maze.get(j as usize)?.get(i as usize).copied()
+}
+
+// Intersección con el panel de una puerta, hundido a media celda.
+// Devuelve la distancia y la posición del impacto a lo largo del panel.
+fn door_hit(pos: Vec2, dir: Vec2, i: isize, j: isize, block: f32, cell: char, open: f32) -> Option<(f32, f32)> {
+    // 'D' tiene el panel a lo largo del eje x, 'd' a lo largo del eje y
+    let (pos_across, dir_across, pos_along, dir_along, cell_across, cell_along) = if cell == 'D' {
+        (pos.y, dir.y, pos.x, dir.x, j as f32, i as f32)
+    } else {
+        (pos.x, dir.x, pos.y, dir.y, i as f32, j as f32)
+    };
+
+    if dir_across == 0.0 {
+        return None;
+    }
+
+    let plane = cell_across * block + block / 2.0;
+    let t = (plane - pos_across) / dir_across;
+    let offset = pos_along + dir_along * t - cell_along * block;
+
+    // El panel se desliza dejando libre la parte [0, open * block) de la celda
+    if t < 0.0 || offset < open * block || offset >= block {
+        return None;
+    }
+
+    Some((t, offset - open * block))
+}
+
+// Recorre el laberinto desde `origin` con ángulo `a` hasta chocar con una pared.
+// `inventory` decide qué barreras están abiertas.
+pub fn trace_ray(
+    maze: &[Vec<char>],
+    doors: &Doors,
+    origin: Vec2,
+    inventory: &[Keycard],
+    a: f32,
+    block_size: usize,
+) -> Intersect {
+    let block = block_size as f32;
+    let dir = Vec2::new(a.cos(), a.sin());
+
+    // Celda inicial del rayo
+    let mut i = (origin.x / block).floor() as isize;
+    let mut j = (origin.y / block).floor() as isize;
+
+    // Distancia que recorre el rayo para cruzar una celda completa en cada eje
+    let delta_x = if dir.x == 0.0 { f32::INFINITY } else { (block / dir.x).abs() };
+    let delta_y = if dir.y == 0.0 { f32::INFINITY } else { (block / dir.y).abs() };
+
+    // Distancia hasta el primer borde de celda en cada eje
+    let (step_x, mut side_x) = if dir.x < 0.0 {
+        (-1, (origin.x - i as f32 * block) / -dir.x)
+    } else if dir.x > 0.0 {
+        (1, ((i + 1) as f32 * block - origin.x) / dir.x)
+    } else {
+        (0, f32::INFINITY)
+    };
+    let (step_y, mut side_y) = if dir.y < 0.0 {
+        (-1, (origin.y - j as f32 * block) / -dir.y)
+    } else if dir.y > 0.0 {
+        (1, ((j + 1) as f32 * block - origin.y) / dir.y)
+    } else {
+        (0, f32::INFINITY)
+    };
+
+    let mut distance;
+    let mut face;
+    let impact;
+    let mut door_offset = None;
+
+    // Avanzar de celda en celda hasta chocar con algo que no sea espacio vacío
+    loop {
+        if side_x < side_y {
+            distance = side_x;
+            side_x += delta_x;
+            i += step_x;
+            face = if step_x > 0 { Face::West } else { Face::East };
+        } else {
+            distance = side_y;
+            side_y += delta_y;
+            j += step_y;
+            face = if step_y > 0 { Face::North } else { Face::South };
+        }
+
+        match cell_at(maze, i, j) {
+            Some(' ') => continue,
+            // Los objetos se dibujan como sprites y las barreras abiertas desaparecen
+            Some(cell) if Keycard::from_pickup(cell).is_some() => continue,
+            Some(cell) if is_open_barrier(cell, inventory) => continue,
+            Some(cell) if is_door(cell) => {
+                let open = doors.openness(i as usize, j as usize);
+                if let Some((t, offset)) = door_hit(origin, dir, i, j, block, cell, open) {
+                    distance = t;
+                    face = match (cell, dir.x > 0.0, dir.y > 0.0) {
+                        ('D', _, true) => Face::North,
+                        ('D', _, false) => Face::South,
+                        (_, true, _) => Face::West,
+                        (_, false, _) => Face::East,
+                    };
+                    door_offset = Some(offset);
+                    impact = cell;
+                    break;
+                }
+            }
+            Some(cell) => {
+                impact = cell;
+                break;
+            }
+            // El rayo salió del laberinto: se trata como el borde del mapa
+            None => {
+                impact = ' ';
+                break;
+            }
+        }
+    }
+
+    if !distance.is_finite() {
+        distance = 0.0;
+        face = Face::North;
+    }
+
+    let hit = origin + dir * distance;
+
+    // Posición del impacto a lo largo de la cara golpeada
+    let offset = door_offset.unwrap_or(match face {
+        Face::East | Face::West => hit.y - j as f32 * block,
+        Face::North | Face::South => hit.x - i as f32 * block,
+    });
+    let u = (offset / block).clamp(0.0, 1.0 - f32::EPSILON);
+
+    Intersect {
+        distance,
+        impact,
+        u,
+        hit,
+        cell: (i.max(0) as usize, j.max(0) as usize),
+        face,
+    }
 }
 
 pub fn cast_ray(
     framebuffer: &mut Framebuffer,
-    maze: &Vec<Vec<char>>,
+    maze: &[Vec<char>],
+    doors: &Doors,
     player: &Player,
     a: f32,
     block_size: usize,
     draw_line: bool,
 ) -> Intersect {
-    let mut d = 0.0;
+    let intersect = trace_ray(maze, doors, player.pos, &player.inventory, a, block_size);
+
+    if draw_line {
+        framebuffer.set_current_color(0xFFFFFF);
+        let dir = Vec2::new(a.cos(), a.sin());
+        let mut d = 0.0;
+        while d < intersect.distance {
+            let p = player.pos + dir * d;
+            framebuffer.point(p.x as usize, p.y as usize);
+            d += 1.0;
+        }
+    }
 
-    framebuffer.set_current_color(0xFFFFFF);
+    intersect
+}
 
-    loop {
-        let cos = d * a.cos();
-        let sin = d * a.sin();
-        let x = (player.pos.x + cos) as usize;
-        let y = (player.pos.y + sin) as usize;
+// Distancia del jugador al plano de proyección, derivada del ancho de pantalla y el FOV
+pub fn projection_plane_distance(screen_width: usize, fov: f32) -> f32 {
+    (screen_width as f32 / 2.0) / (fov / 2.0).tan()
+}
 
-        let i = x / block_size;
-        let j = y / block_size;
+// Punto del piso (o techo) que se ve a `rows_from_horizon` filas del horizonte y su distancia
+// al jugador. `direction` es el vector unitario del rayo de la columna y `cos_offset` el coseno
+// del ángulo entre ese rayo y la vista; son iguales en toda la columna, así que se calculan
+// una vez afuera.
+pub fn surface_point(
+    player: &Player,
+    direction: Vec2,
+    cos_offset: f32,
+    rows_from_horizon: f32,
+    block_size: usize,
+    distance_to_projection_plane: f32,
+) -> (Vec2, f32) {
+    // La cámara está a media altura de bloque, igual que el centro de las paredes
+    let row_distance = (block_size as f32 / 2.0) * distance_to_projection_plane / rows_from_horizon;
+    let distance = row_distance / cos_offset;
+    (player.pos + direction * distance, distance)
+}
 
-        let hitx = x - i*block_size;
-        let hity = y - j*block_size;
-        let mut maxhit = hity;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
 
-        if 1 < hitx && hitx < block_size - 1 {
-            maxhit = hitx
-        } 
+    const BLOCK: usize = 10;
 
-        if draw_line {
-            framebuffer.point(x, y);
-        }
+    fn maze(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|row| row.chars().collect()).collect()
+    }
 
-        if maze[j][i] != ' ' {
-            return Intersect{
-                distance: d,
-                impact: maze[j][i],
-                tx: (maxhit * 128) / block_size,
-            };
-        }
+    fn trace(maze: &[Vec<char>], doors: &Doors, origin: Vec2, a: f32) -> Intersect {
+        trace_ray(maze, doors, origin, &[], a, BLOCK)
+    }
 
-        d += 1.0;
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "{} != {}", actual, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn axis_aligned_rays_hit_the_facing_wall() {
+        let maze = maze(&["######", "#    #", "#    #", "######"]);
+        let doors = Doors::from_maze(&maze);
+        let origin = Vec2::new(15.0, 15.0);
+
+        // a = 0 deja dir.y exactamente en 0
+        let east = trace(&maze, &doors, origin, 0.0);
+        assert_eq!((east.face, east.cell, east.impact), (Face::West, (5, 1), '#'));
+        assert_close(east.distance, 35.0);
+        assert_close(east.u, 0.5);
+
+        let west = trace(&maze, &doors, origin, PI);
+        assert_eq!((west.face, west.cell), (Face::East, (0, 1)));
+        assert_close(west.distance, 5.0);
+        assert_close(west.u, 0.5);
+
+        // cos(PI / 2) en f32 no es exactamente 0, pero el rayo es vertical
+        let south = trace(&maze, &doors, origin, PI / 2.0);
+        assert_eq!((south.face, south.cell), (Face::North, (1, 3)));
+        assert_close(south.distance, 15.0);
+        assert_close(south.u, 0.5);
+
+        let north = trace(&maze, &doors, origin, PI * 1.5);
+        assert_eq!((north.face, north.cell), (Face::South, (1, 0)));
+        assert_close(north.distance, 5.0);
+    }
+
+    #[test]
+    fn diagonal_ray_reports_distance_and_u() {
+        let maze = maze(&["######", "#    #", "#    #", "######"]);
+        let doors = Doors::from_maze(&maze);
+        let hit = trace(&maze, &doors, Vec2::new(12.0, 15.0), PI / 4.0);
+        assert_eq!((hit.face, hit.cell), (Face::North, (2, 3)));
+        assert_close(hit.distance, 15.0 * 2f32.sqrt());
+        assert_close(hit.u, 0.7);
+    }
+
+    #[test]
+    fn closed_door_is_hit_half_a_block_in() {
+        let maze = maze(&["#####", "#  d#", "#####"]);
+        let mut doors = Doors::from_maze(&maze);
+        let origin = Vec2::new(15.0, 15.0);
+
+        let hit = trace(&maze, &doors, origin, 0.0);
+        assert_eq!((hit.face, hit.cell, hit.impact), (Face::West, (3, 1), 'd'));
+        assert_close(hit.distance, 20.0);
+        assert_close(hit.u, 0.5);
+
+        // Abierta del todo, el rayo pasa y choca con la pared de atrás
+        doors.toggle(3, 1);
+        doors.update(10.0);
+        let hit = trace(&maze, &doors, origin, 0.0);
+        assert_eq!((hit.cell, hit.impact), ((4, 1), '#'));
+        assert_close(hit.distance, 25.0);
+    }
+
+    #[test]
+    fn horizontal_door_is_hit_by_vertical_rays() {
+        let maze = maze(&["###", "# #", "#D#", "# #", "###"]);
+        let doors = Doors::from_maze(&maze);
+        let hit = trace(&maze, &doors, Vec2::new(13.0, 15.0), PI / 2.0);
+        assert_eq!((hit.face, hit.cell, hit.impact), (Face::North, (1, 2), 'D'));
+        assert_close(hit.distance, 10.0);
+        assert_close(hit.u, 0.3);
+    }
+}
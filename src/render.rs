@@ -0,0 +1,351 @@
+// render.rs
+
+use nalgebra_glm::Vec2;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+use crate::framebuffer::Framebuffer;
+use crate::maze::Level;
+use crate::player::{Player, BLOCK_SIZE};
+use crate::raycast::{cast_ray, projection_plane_distance, surface_point};
+use crate::texture::{Texture, TextureFilter, LEGACY_COLOR_KEY};
+use crate::enemy::{Enemy, ENEMY_DIRECTIONS, ENEMY_SHEET};
+use crate::lighting::{Lighting, mix_color};
+use crate::door::Doors;
+use crate::item::Keycard;
+use crate::combat::Projectile;
+use crate::sprite::{direction_column, Sprite, SpriteImage};
+use crate::theme::Theme;
+
+// El arma no tiene canal alfa: su fondo rojo se vuelve transparente con la clave de color
+static UI_SPRITE: Lazy<Arc<Texture>> = Lazy::new(|| Arc::new(Texture::new("assets/player2.png").with_color_key(LEGACY_COLOR_KEY)));
+const UI_REFERENCE_HEIGHT: f32 = 635.0; // alto de la ventana para el que se dibujó el HUD
+
+// Calidad de las texturas; en el juego se cambia con F (filtro) y G (mipmaps)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphicsSettings {
+    pub filter: TextureFilter,
+    pub mipmaps: bool, // usar versiones reducidas de las texturas lejanas
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        GraphicsSettings { filter: TextureFilter::Nearest, mipmaps: true }
+    }
+}
+
+impl GraphicsSettings {
+    // Nivel de `texture` para dibujarla completa con `screen_height` píxeles de alto
+    pub fn level<'a>(&self, texture: &'a Texture, screen_height: f32) -> &'a Texture {
+        if self.mipmaps { texture.mip_for_height(screen_height) } else { texture }
+    }
+
+    // Descripción corta para el título de la ventana
+    pub fn describe(&self) -> String {
+        let filter = match self.filter {
+            TextureFilter::Nearest => "cercano",
+            TextureFilter::Bilinear => "bilineal",
+        };
+        format!("Filtro: {}{}", filter, if self.mipmaps { " + mipmaps" } else { "" })
+    }
+}
+
+// Color de la celda en la vista 2D y el minimapa
+fn cell_to_color(cell: char, theme: &Theme) -> u32 {
+    match cell {
+        ' ' => 0x000000,
+        _ => match Keycard::from_pickup(cell) {
+            Some(key) => key.color(),
+            None => theme.style(cell).minimap_color,
+        },
+    }
+}
+
+// Llena `out` con la columna `u` (0 a 1) de `texture`, la textura de la pared `cell`,
+// escalada según `v_start` y `v_step`
+fn wall_column(texture: &Texture, cell: char, u: f32, v_start: f32, v_step: f32, filter: TextureFilter, out: &mut [u32]) {
+    texture.sample_column(u, v_start, v_step, filter, out);
+    // Las barreras se tiñen con el color de su tarjeta
+    match Keycard::from_barrier(cell) {
+        Some(key) => out.iter_mut().for_each(|color| *color = mix_color(*color & 0xFFFFFF, key.color(), 0.5)),
+        None => out.iter_mut().for_each(|color| *color &= 0xFFFFFF),
+    }
+}
+
+// Textura de piso/techo para la celda según el tema, que en pantalla mide unos
+// `screen_size` píxeles; None conserva el color plano por defecto
+fn surface_texture_color(theme: &Theme, cell: char, fx: f32, fy: f32, screen_size: f32, graphics: &GraphicsSettings) -> Option<u32> {
+    let texture = theme.surface(cell)?;
+    Some(graphics.level(texture, screen_size).sample(fx, fy, graphics.filter) & 0xFFFFFF)
+}
+
+fn draw_cell(framebuffer: &mut Framebuffer, xo: usize, yo: usize, block_size: usize, cell: char, theme: &Theme) {
+    for x in xo..xo + block_size {
+        for y in yo..yo + block_size {
+            if cell != ' ' {
+                let color = cell_to_color(cell, theme);
+                framebuffer.set_current_color(color);
+                framebuffer.point(x, y);               
+            }
+        }
+    }   
+}
+
+pub fn render_ui(framebuffer: &mut Framebuffer, player: &Player) {
+    // El arma mide 512x512 en la ventana de 635 de alto; en cuadros más bajos se achica
+    let scale = (framebuffer.height as f32 / UI_REFERENCE_HEIGHT).min(1.0);
+    let ui_size = ((UI_SPRITE.width as f32 * scale) as u32).max(1);
+    // Centrado abajo; si aun así no cabe a lo ancho se recorta lo que sobra
+    let ui_x = framebuffer.width as i64 / 2 - ui_size as i64 / 2;
+    let ui_y = framebuffer.height as i64 - ui_size as i64;
+
+    for y in 0..ui_size {
+        for x in 0..ui_size {
+            let (screen_x, screen_y) = (ui_x + x as i64, ui_y + y as i64);
+            if screen_x < 0 || screen_y < 0 {
+                continue; // blend_point descarta lo que sale por la derecha o por abajo
+            }
+            let color = UI_SPRITE.get_pixel_argb(x * UI_SPRITE.width / ui_size, y * UI_SPRITE.height / ui_size);
+            framebuffer.set_current_color(color & 0xFFFFFF);
+            framebuffer.blend_point(screen_x as usize, screen_y as usize, (color >> 24) as u8);
+        }
+    }
+
+    // Vida, armadura y munición en la esquina inferior derecha
+    framebuffer.set_current_color(0xFFFFFF);
+    let hud_x = framebuffer.width.saturating_sub(235);
+    framebuffer.draw_text(&format!("VIDA     {:>3}", player.health.ceil() as u32), hud_x, framebuffer.height.saturating_sub(60), 2);
+    framebuffer.draw_text(&format!("ARMADURA {:>3}", player.armor.ceil() as u32), hud_x, framebuffer.height.saturating_sub(40), 2);
+    framebuffer.draw_text(&format!("BALAS    {:>3}", player.ammo), hud_x, framebuffer.height.saturating_sub(20), 2);
+
+    // Tarjetas recogidas en la esquina inferior izquierda
+    let key_size = 20;
+    let keys_y = framebuffer.height.saturating_sub(key_size + 10);
+    for (i, key) in player.inventory.iter().enumerate() {
+        let keys_x = 10 + i * (key_size + 5);
+        framebuffer.set_current_color(key.color());
+        for x in keys_x..keys_x + key_size {
+            for y in keys_y..keys_y + key_size / 2 {
+                framebuffer.point(x, y);
+            }
+        }
+    }
+}
+
+pub fn render2d(framebuffer: &mut Framebuffer, player: &Player, level: &Level, doors: &Doors) {
+    
+    let block_size = 70; //100
+    let maze = &level.maze;
+  
+    // draw the minimap
+    for row in 0..maze.len() {
+        for col in 0..maze[row].len() {
+            draw_cell(framebuffer, col * block_size, row * block_size, block_size, maze[row][col], &level.theme);
+        }
+    }
+    // draw the player
+    framebuffer.set_current_color(0xFFDDDD);
+    framebuffer.point(player.pos.x as usize, player.pos.y as usize);
+  
+    // draw what the player sees
+    let num_rays = 50;
+    for i in 0..num_rays {
+        let current_ray = i as f32 / num_rays as f32; // current ray divided by total rays
+        let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
+        cast_ray(framebuffer, &maze, doors, &player, a, block_size, true);
+    }
+}
+  
+// `time` es el tiempo de juego en segundos, para las paredes animadas
+// Dibuja paredes, piso y techo; devuelve el z-buffer con la distancia perpendicular
+// a la pared de cada columna
+pub fn render3d(
+    framebuffer: &mut Framebuffer,
+    player: &Player,
+    level: &Level,
+    doors: &Doors,
+    lighting: &Lighting,
+    graphics: &GraphicsSettings,
+    time: f32,
+) -> Vec<f32> {
+    
+    let block_size = 70; 
+    let num_rays = framebuffer.width;
+  
+    // Precalculate half height of the framebuffer
+    let hh = framebuffer.height as f32 / 2.0;  
+  
+    // Plano de proyección según el ancho de pantalla y el FOV del jugador
+    let distance_to_projection_plane = projection_plane_distance(framebuffer.width, player.fov);
+    let half_width = framebuffer.width as f32 / 2.0;
+    let mut column = vec![0; framebuffer.height];
+    let mut z_buffer = vec![f32::INFINITY; framebuffer.width];
+  
+    for i in 0..num_rays {
+        // Ángulo del rayo que pasa por el centro de la columna i del plano de proyección
+        let screen_x = i as f32 + 0.5 - half_width;
+        let a = player.a + (screen_x / distance_to_projection_plane).atan();
+        let direction = Vec2::new(a.cos(), a.sin());
+        let cos_offset = (a - player.a).cos();
+        let intersect = cast_ray(framebuffer, &level.maze, doors, &player, a, block_size, false);
+
+        // Distancia perpendicular al plano para corregir el efecto ojo de pez
+        let distance_to_wall = intersect.distance * cos_offset;
+        let stake_height = (block_size as f32 / distance_to_wall) * distance_to_projection_plane;
+
+        let stake_top = hh - (stake_height / 2.0);
+        let stake_bottom = hh + (stake_height / 2.0);
+
+        z_buffer[i] = distance_to_wall;
+
+        // La luz de la pared es la de la celda libre frente a la cara golpeada
+        let lit_pos = intersect.hit - direction;
+
+        let y_start = stake_top.max(0.0) as usize;
+        let y_end = (stake_bottom.max(0.0) as usize).min(framebuffer.height);
+  
+        // Toda la tira de textura de una vez; v va de 0 (arriba) a 1 (abajo) de la pared
+        let strip = &mut column[y_start..y_end.max(y_start)];
+        let v_start = ((y_start as f32 - stake_top) / stake_height).max(0.0);
+        let texture = graphics.level(level.theme.style(intersect.impact).texture_at(time), stake_height);
+        wall_column(texture, intersect.impact, intersect.u, v_start, 1.0 / stake_height, graphics.filter, strip);
+        for (y, &color) in (y_start..).zip(strip.iter()) {
+            framebuffer.set_current_color(lighting.shade_wall(color, intersect.distance, lit_pos, intersect.face));
+            framebuffer.point(i, y);
+        }
+
+        // draw the ceiling above the wall and the floor below it
+        for y in (0..y_start).chain(y_end..framebuffer.height) {
+            let is_ceiling = (y as f32) < hh;
+            let rows_from_horizon = (y as f32 + 0.5 - hh).abs();
+            let (point, distance) = surface_point(player, direction, cos_offset, rows_from_horizon, block_size, distance_to_projection_plane);
+
+            let block = block_size as f32;
+            let col = (point.x / block).floor().max(0.0) as usize;
+            let row = (point.y / block).floor().max(0.0) as usize;
+            let fx = point.x.rem_euclid(block) / block;
+            let fy = point.y.rem_euclid(block) / block;
+
+            let screen_size = block * distance_to_projection_plane / distance.max(1.0);
+            let color = if is_ceiling {
+                surface_texture_color(&level.theme, level.surfaces.ceiling_at(col, row), fx, fy, screen_size, graphics).unwrap_or(0x2B2E3D)
+            } else {
+                surface_texture_color(&level.theme, level.surfaces.floor_at(col, row), fx, fy, screen_size, graphics).unwrap_or(0x222530)
+            };
+            framebuffer.set_current_color(lighting.shade(color, distance, point));
+            framebuffer.point(i, y);
+        }
+    }
+    z_buffer
+}
+  
+// Tarjetas que siguen en el laberinto, en el piso al centro de su celda
+pub fn item_sprites(maze: &[Vec<char>]) -> Vec<Sprite<'static>> {
+    let mut sprites = Vec::new();
+    for (row, line) in maze.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            if let Some(key) = Keycard::from_pickup(cell) {
+                sprites.push(Sprite {
+                    pos: Vec2::new(col as f32 + 0.5, row as f32 + 0.5) * BLOCK_SIZE,
+                    elevation: 0.0,
+                    width: 20.0,
+                    height: 20.0,
+                    image: SpriteImage::Color(key.color()),
+                });
+            }
+        }
+    }
+    sprites
+}
+
+// Disparos enemigos: pequeños cuadros brillantes a la altura de los ojos
+pub fn projectile_sprites(projectiles: &[Projectile]) -> Vec<Sprite<'static>> {
+    projectiles
+        .iter()
+        .map(|projectile| Sprite {
+            pos: projectile.pos,
+            elevation: 31.0,
+            width: 8.0,
+            height: 8.0,
+            image: SpriteImage::Color(0xFF5533),
+        })
+        .collect()
+}
+
+// Enemigos (y sus cuerpos) con el cuadro de animación y la vista según desde dónde se miran
+pub fn enemy_sprites(enemies: &[Enemy], viewer: &Player) -> Vec<Sprite<'static>> {
+    enemies
+        .iter()
+        .map(|enemy| {
+            let (width, height) = enemy.kind.sprite_size();
+            let clip = enemy.clip();
+            let column = if clip.directional {
+                direction_column(enemy.facing, enemy.pos, viewer.pos, ENEMY_DIRECTIONS)
+            } else {
+                0
+            };
+            Sprite {
+                pos: enemy.pos,
+                elevation: 0.0,
+                width,
+                height,
+                image: SpriteImage::Frame { sheet: &ENEMY_SHEET, row: clip.row_at(enemy.animation_time), column },
+            }
+        })
+        .collect()
+}
+
+pub fn render_minimap(framebuffer: &mut Framebuffer, level: &Level, player: &Player) {
+    let maze = &level.maze;
+
+    // Configuración del minimapa
+    let minimap_size = 100; // Tamaño total del minimapa
+    let cell_size = minimap_size / maze.len().max(1); // Tamaño de cada celda del minimapa
+    if cell_size == 0 {
+        return; // Laberinto de más de `minimap_size` filas: no cabe
+    }
+    let minimap_x = framebuffer.width.saturating_sub(minimap_size + 200); // Margen derecho
+    let minimap_y = 5; // Margen superior
+
+    // Dibujar el minimapa
+    for (row, line) in maze.iter().enumerate() {
+		for (col, &cell) in line.iter().enumerate() {
+			let x = minimap_x + col * cell_size;
+			let y = minimap_y + row * cell_size;
+	
+			// Dibujar la celda solo si está dentro de los límites del framebuffer
+			if x + cell_size < framebuffer.width && y + cell_size < framebuffer.height {
+				let color = cell_to_color(cell, &level.theme);
+	
+				for i in 0..cell_size {
+					for j in 0..cell_size {
+						framebuffer.set_current_color(color);
+						framebuffer.point(x + i, y + j);
+					}
+				}
+			}
+		}
+	}
+
+    // Dibujar al jugador en el minimapa
+    let player_col = (player.pos.x / BLOCK_SIZE) as usize; // Escalado del jugador según el bloque
+    let player_row = (player.pos.y / BLOCK_SIZE) as usize;
+    let player_x = minimap_x + player_col * cell_size + cell_size / 2;
+    let player_y = minimap_y + player_row * cell_size + cell_size / 2;
+
+    let player_color = 0xFF0000; // Color rojo para el jugador
+    let player_radius = (cell_size / 4) as isize;
+
+    for i in -player_radius..=player_radius {
+		for j in -player_radius..=player_radius {
+			let px = player_x as isize + i;
+			let py = player_y as isize + j;
+	
+			// Asegurarnos de que px y py están dentro de los límites
+			if px >= 0 && py >= 0 && px < framebuffer.width as isize && py < framebuffer.height as isize {
+				framebuffer.set_current_color(player_color);
+				framebuffer.point(px as usize, py as usize); // Convertir de nuevo a usize para dibujar
+			}
+		}
+	}
+}
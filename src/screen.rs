@@ -1,5 +1,6 @@
 // screen.rs
 
+use crate::error::GameError;
 use crate::framebuffer::Framebuffer;
 use crate::texture::Texture;
 
@@ -16,9 +17,10 @@ impl Screen {
         }
     }
 
-    // Establecer una imagen de fondo
-    pub fn set_background(&mut self, image_path: &str) {
-        self.background = Some(Texture::new(image_path));
+    // Establecer una imagen de fondo; si no se puede cargar, la pantalla queda sin fondo
+    pub fn set_background(&mut self, image_path: &str) -> Result<(), GameError> {
+        self.background = Some(Texture::load(image_path)?);
+        Ok(())
     }
 
     // Agregar texto con color
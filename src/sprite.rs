@@ -0,0 +1,178 @@
+// sprite.rs
+
+use nalgebra_glm::Vec2;
+use std::f32::consts::PI;
+
+use crate::framebuffer::Framebuffer;
+use crate::lighting::Lighting;
+use crate::player::Player;
+use crate::raycast::projection_plane_distance;
+use crate::render::GraphicsSettings;
+use crate::texture::{Texture, TextureFilter};
+
+const EYE_HEIGHT: f32 = 35.0; // Altura de la cámara sobre el piso (medio bloque)
+const NEAR_PLANE: f32 = 1.0;  // Los sprites más cerca que esto no se dibujan
+
+// Hoja de sprites: una textura dividida en una cuadrícula de cuadros del mismo tamaño
+pub struct SpriteSheet {
+    pub texture: Texture,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+impl SpriteSheet {
+    pub fn new(texture: Texture, columns: u32, rows: u32) -> Self {
+        SpriteSheet { texture, columns, rows }
+    }
+
+    pub fn frame_width(&self) -> u32 {
+        self.texture.width / self.columns
+    }
+
+    pub fn frame_height(&self) -> u32 {
+        self.texture.height / self.rows
+    }
+
+    // Color ARGB en (u, v), entre 0 y 1, del cuadro en (row, column). `level` es la hoja
+    // o una de sus versiones reducidas; (u, v) se queda a medio texel del borde del cuadro
+    // para que el filtrado no mezcle cuadros vecinos.
+    pub fn sample(&self, row: u32, column: u32, u: f32, v: f32, level: &Texture, filter: TextureFilter) -> u32 {
+        let half_u = 0.5 * self.columns as f32 / level.width as f32;
+        let half_v = 0.5 * self.rows as f32 / level.height as f32;
+        let u = u.max(half_u).min(1.0 - half_u);
+        let v = v.max(half_v).min(1.0 - half_v);
+        let sheet_u = (column.min(self.columns - 1) as f32 + u) / self.columns as f32;
+        let sheet_v = (row.min(self.rows - 1) as f32 + v) / self.rows as f32;
+        level.sample(sheet_u, sheet_v, filter)
+    }
+}
+
+// Cuadro de una animación: fila de la hoja y cuánto dura en segundos
+pub struct ClipFrame {
+    pub row: u32,
+    pub duration: f32,
+}
+
+// Animación: una secuencia de filas de la hoja. En las animaciones direccionales
+// la columna depende del ángulo de vista; en las demás siempre es la columna 0.
+pub struct AnimationClip {
+    pub frames: &'static [ClipFrame],
+    pub looping: bool,
+    pub directional: bool,
+}
+
+impl AnimationClip {
+    // Fila de la hoja que corresponde a `time` segundos desde que empezó la animación;
+    // las que no se repiten se quedan en el último cuadro
+    pub fn row_at(&self, time: f32) -> u32 {
+        let total: f32 = self.frames.iter().map(|frame| frame.duration).sum();
+        let mut time = if self.looping && total > 0.0 { time.rem_euclid(total) } else { time };
+        for frame in self.frames {
+            if time < frame.duration {
+                return frame.row;
+            }
+            time -= frame.duration;
+        }
+        self.frames.last().map_or(0, |frame| frame.row)
+    }
+}
+
+// Columna de una hoja con `directions` vistas para un objeto que mira hacia `facing`,
+// visto desde `viewer`. La columna 0 es de frente y las siguientes giran en sentido
+// de los ángulos crecientes, `360 / directions` grados cada una.
+pub fn direction_column(facing: f32, pos: Vec2, viewer: Vec2, directions: u32) -> u32 {
+    let to_viewer = viewer - pos;
+    let relative = (to_viewer.y.atan2(to_viewer.x) - facing).rem_euclid(2.0 * PI);
+    let step = 2.0 * PI / directions as f32;
+    ((relative / step).round() as u32) % directions
+}
+
+pub enum SpriteImage<'a> {
+    Texture(&'a Texture),
+    Frame { sheet: &'a SpriteSheet, row: u32, column: u32 }, // cuadro de una hoja de sprites
+    Color(u32), // cuadro opaco de un solo color (RGB)
+}
+
+// Imagen plana que siempre mira a la cámara, parada en `pos` del mundo
+pub struct Sprite<'a> {
+    pub pos: Vec2,
+    pub elevation: f32, // altura del borde inferior sobre el piso
+    pub width: f32,     // tamaño en unidades del mundo
+    pub height: f32,
+    pub image: SpriteImage<'a>,
+}
+
+// Dibuja los sprites de atrás hacia adelante, mezclando según el alfa de sus texturas. `z_buffer` tiene la distancia perpendicular
+// a la pared de cada columna; solo se lee, así que los sprites cercanos tapan a los lejanos
+// por el orden de dibujo y las paredes tapan a los sprites por la prueba de profundidad.
+pub fn render_sprites(
+    framebuffer: &mut Framebuffer,
+    player: &Player,
+    mut sprites: Vec<Sprite>,
+    z_buffer: &[f32],
+    lighting: &Lighting,
+    graphics: &GraphicsSettings,
+) {
+    // Ejes de la cámara: en el espacio de la cámara no hay que normalizar ángulos,
+    // así que no hay problemas al cruzar de 2π a 0
+    let forward = Vec2::new(player.a.cos(), player.a.sin());
+    let right = Vec2::new(-forward.y, forward.x);
+    let depth = |sprite: &Sprite| (sprite.pos - player.pos).dot(&forward);
+
+    sprites.sort_by(|a, b| depth(b).total_cmp(&depth(a)));
+
+    let distance_to_projection_plane = projection_plane_distance(framebuffer.width, player.fov);
+    let half_width = framebuffer.width as f32 / 2.0;
+    let half_height = framebuffer.height as f32 / 2.0;
+
+    for sprite in &sprites {
+        let offset = sprite.pos - player.pos;
+        let depth = offset.dot(&forward);
+        if depth < NEAR_PLANE {
+            continue; // Detrás de la cámara o demasiado cerca
+        }
+
+        let scale = distance_to_projection_plane / depth;
+        let screen_width = sprite.width * scale;
+        let screen_height = sprite.height * scale;
+        let left = half_width + offset.dot(&right) * scale - screen_width / 2.0;
+        let top = half_height + (EYE_HEIGHT - sprite.elevation) * scale - screen_height;
+
+        // Recorte contra los bordes; las coordenadas de textura salen de `left`/`top` sin recortar
+        let start_x = left.max(0.0) as usize;
+        let end_x = ((left + screen_width).max(0.0) as usize).min(framebuffer.width);
+        let start_y = top.max(0.0) as usize;
+        let end_y = ((top + screen_height).max(0.0) as usize).min(framebuffer.height);
+
+        // Nivel de detalle según el alto en pantalla (la hoja completa mide `rows` cuadros)
+        let level = match sprite.image {
+            SpriteImage::Texture(texture) => Some(graphics.level(texture, screen_height)),
+            SpriteImage::Frame { sheet, .. } => Some(graphics.level(&sheet.texture, screen_height * sheet.rows as f32)),
+            SpriteImage::Color(_) => None,
+        };
+
+        let distance = offset.magnitude();
+        for (x, &wall_depth) in z_buffer.iter().enumerate().take(end_x).skip(start_x) {
+            if depth >= wall_depth {
+                continue; // Una pared tapa esta columna
+            }
+            let u = (x as f32 + 0.5 - left) / screen_width;
+            for y in start_y..end_y {
+                let v = (y as f32 + 0.5 - top) / screen_height;
+                let color = match sprite.image {
+                    SpriteImage::Texture(texture) => level.unwrap_or(texture).sample(u, v, graphics.filter),
+                    SpriteImage::Frame { sheet, row, column } => {
+                        sheet.sample(row, column, u, v, level.unwrap_or(&sheet.texture), graphics.filter)
+                    }
+                    SpriteImage::Color(color) => 0xFF000000 | color,
+                };
+                let alpha = (color >> 24) as u8;
+                if alpha == 0 {
+                    continue;
+                }
+                framebuffer.set_current_color(lighting.shade(color & 0xFFFFFF, distance, sprite.pos));
+                framebuffer.blend_point(x, y, alpha);
+            }
+        }
+    }
+}
@@ -2,49 +2,390 @@
 
 extern crate image;
 
-use image::{ImageReader, Pixel};
-use image::{DynamicImage, GenericImageView};
+use image::{DynamicImage, ImageReader};
+use crate::error::GameError;
+
+// Color que los recursos viejos usan como fondo transparente (ver `with_color_key`)
+pub const LEGACY_COLOR_KEY: u32 = 0xED1C24;
+
+const MISSING_COLOR: u32 = 0xFFFF00FF;
+
+// Bits fraccionarios del punto fijo con el que se recorre una columna
+const FRACTION_BITS: u32 = 16;
+
+// Cómo se lee un texel cuando la textura no coincide píxel a píxel con la pantalla
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureFilter {
+    Nearest,  // el texel más cercano (pixelado)
+    Bilinear, // mezcla de los cuatro texels vecinos (suave)
+}
 
 pub struct Texture {
-    image: DynamicImage,
     pub width: u32,
     pub height: u32,
-    color_array: Vec<Vec<u32>>,
+    // Colores ARGB por columnas: (x, y) está en `x * height + y`, así que una tira
+    // vertical de pared lee memoria contigua. El alfa viene del PNG.
+    pixels: Vec<u32>,
+    // Versiones reducidas a la mitad, a la cuarta parte, ... hasta 1x1
+    mips: Vec<Texture>,
 }
 
 impl Texture {
+    // Carga la textura o devuelve una textura de error visible si el archivo falla
     pub fn new(file_path: &str) -> Texture {
-        let img = ImageReader::open(file_path).unwrap().decode().unwrap();
-        let width = img.width();
-        let height = img.height();
-        let mut texture = Texture { 
-            image: img, 
-            width, 
-            height, 
-            color_array: vec![vec![0; height as usize]; width as usize],
-        };
-        texture.load_color_array();
+        Texture::load(file_path).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            Texture::missing()
+        })
+    }
+
+    pub fn load(file_path: &str) -> Result<Texture, GameError> {
+        let img = ImageReader::open(file_path)
+            .map_err(|source| GameError::Io { path: file_path.to_string(), source })?
+            .decode()
+            .map_err(|source| GameError::Image { path: file_path.to_string(), source })?;
+        Ok(Texture::from_image(img))
+    }
+
+    // Tablero magenta y negro para texturas que no se pudieron cargar
+    pub fn missing() -> Texture {
+        Texture::from_fn(64, 64, |x, y| {
+            if (x / 8 + y / 8) % 2 == 0 { MISSING_COLOR } else { 0xFF000000 }
+        })
+    }
+
+    // La imagen decodificada no se guarda: solo queda el arreglo de colores
+    pub fn from_image(img: DynamicImage) -> Texture {
+        let img = img.into_rgba8();
+        Texture::from_fn(img.width(), img.height(), |x, y| {
+            let [r, g, b, a] = img.get_pixel(x, y).0;
+            ((a as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
+        })
+    }
+
+    // Textura con el color ARGB que devuelve `color(x, y)` en cada texel
+    pub fn from_fn(width: u32, height: u32, color: impl Fn(u32, u32) -> u32) -> Texture {
+        let mut texture = Texture::level_from_fn(width, height, color);
+        texture.build_mips();
         texture
     }
 
-    fn load_color_array(&mut self) {
-        for x in 0..self.width {
-            for y in 0..self.height {
-                let pixel = self.image.get_pixel(x, y).to_rgb();
-                let r = pixel[0];
-                let g = pixel[1];
-                let b = pixel[2];
-                let color = ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
-                self.color_array[x as usize][y as usize] = color;
+    // Un solo nivel, sin versiones reducidas
+    fn level_from_fn(width: u32, height: u32, color: impl Fn(u32, u32) -> u32) -> Texture {
+        let pixels = (0..width)
+            .flat_map(|x| (0..height).map(move |y| (x, y)))
+            .map(|(x, y)| color(x, y))
+            .collect();
+        Texture { width, height, pixels, mips: Vec::new() }
+    }
+
+    // Cada nivel promedia bloques de 2x2 del anterior (con el color pesado por el alfa,
+    // para que el fondo transparente no oscurezca los bordes)
+    fn build_mips(&mut self) {
+        self.mips.clear();
+        let (mut width, mut height) = (self.width, self.height);
+        while width > 1 || height > 1 {
+            let previous = self.mips.last().unwrap_or(self);
+            let (prev_width, prev_height) = (width, height);
+            width = (width / 2).max(1);
+            height = (height / 2).max(1);
+            let level = Texture::level_from_fn(width, height, |x, y| {
+                let xs = [(x * 2).min(prev_width - 1), (x * 2 + 1).min(prev_width - 1)];
+                let ys = [(y * 2).min(prev_height - 1), (y * 2 + 1).min(prev_height - 1)];
+                let texels = [
+                    (previous.get_pixel_argb(xs[0], ys[0]), 0.25),
+                    (previous.get_pixel_argb(xs[0], ys[1]), 0.25),
+                    (previous.get_pixel_argb(xs[1], ys[0]), 0.25),
+                    (previous.get_pixel_argb(xs[1], ys[1]), 0.25),
+                ];
+                weighted_average(&texels)
+            });
+            self.mips.push(level);
+        }
+    }
+
+    // Para recursos sin canal alfa: los píxeles del color `key` quedan transparentes
+    pub fn with_color_key(mut self, key: u32) -> Texture {
+        for color in self.pixels.iter_mut() {
+            if *color & 0xFFFFFF == key {
+                *color = key;
             }
         }
+        self.build_mips();
+        self
+    }
+
+    // Nivel de detalle para dibujar la textura completa con `screen_height` píxeles de alto:
+    // el más pequeño que todavía tiene al menos un texel por píxel
+    pub fn mip_for_height(&self, screen_height: f32) -> &Texture {
+        let texels_per_pixel = self.height as f32 / screen_height.max(1.0);
+        if texels_per_pixel < 2.0 || self.mips.is_empty() {
+            return self;
+        }
+        let level = (texels_per_pixel.log2().floor() as usize).min(self.mips.len());
+        &self.mips[level - 1]
     }
 
+    // Color RGB, sin el alfa
     pub fn get_pixel_color(&self, x: u32, y: u32) -> u32 {
+        self.get_pixel_argb(x, y) & 0xFFFFFF
+    }
+
+    // Color con el alfa en el byte alto (0 transparente, 255 opaco)
+    pub fn get_pixel_argb(&self, x: u32, y: u32) -> u32 {
         if x >= self.width || y >= self.height {
-            0xFF00FF
+            MISSING_COLOR
+        } else {
+            self.pixels[(x * self.height + y) as usize]
+        }
+    }
+
+    // Todos los texels de la columna `x`, de arriba hacia abajo
+    pub fn column(&self, x: u32) -> &[u32] {
+        let start = (x.min(self.width - 1) * self.height) as usize;
+        &self.pixels[start..start + self.height as usize]
+    }
+
+    // Índice de la columna (o fila) `i` repetida dentro de `size`
+    fn wrap(i: i64, size: u32) -> u32 {
+        if size.is_power_of_two() {
+            i as u32 & (size - 1)
+        } else {
+            i.rem_euclid(size as i64) as u32
+        }
+    }
+
+    // Color ARGB en (u, v); las coordenadas van de 0 a 1 y se repiten fuera de ese rango
+    pub fn sample(&self, u: f32, v: f32, filter: TextureFilter) -> u32 {
+        let mut texel = [0];
+        self.sample_column(u, v, 0.0, filter, &mut texel);
+        texel[0]
+    }
+
+    // Llena `out` con la columna `u` escalada: el píxel i toma el texel en
+    // `v_start + i * v_step`. Las coordenadas se multiplican por el tamaño real de la
+    // textura, así que sirve para cualquier resolución.
+    pub fn sample_column(&self, u: f32, v_start: f32, v_step: f32, filter: TextureFilter, out: &mut [u32]) {
+        match filter {
+            TextureFilter::Nearest => self.nearest_column(u, v_start, v_step, out),
+            TextureFilter::Bilinear => self.bilinear_column(u, v_start, v_step, out),
+        }
+    }
+
+    // Recorre la columna en punto fijo, sin divisiones; con alto potencia de dos la
+    // repetición es solo una máscara
+    fn nearest_column(&self, u: f32, v_start: f32, v_step: f32, out: &mut [u32]) {
+        let x = Texture::wrap((u * self.width as f32).floor() as i64, self.width);
+        let column = self.column(x);
+        let height = self.height as f32;
+        let one = (1u64 << FRACTION_BITS) as f32;
+        let mut position = (v_start.rem_euclid(1.0) * height * one) as u64;
+        let step = (v_step.max(0.0) * height * one) as u64;
+
+        if self.height.is_power_of_two() {
+            let mask = (self.height - 1) as u64;
+            for texel in out.iter_mut() {
+                *texel = column[((position >> FRACTION_BITS) & mask) as usize];
+                position += step;
+            }
         } else {
-            self.color_array[x as usize][y as usize]
+            let wrap = (self.height as u64) << FRACTION_BITS;
+            for texel in out.iter_mut() {
+                if position >= wrap {
+                    position %= wrap;
+                }
+                *texel = column[(position >> FRACTION_BITS) as usize];
+                position += step;
+            }
         }
     }
-}
\ No newline at end of file
+
+    // Mezcla las dos columnas vecinas de `u` y, en cada píxel, las dos filas vecinas
+    fn bilinear_column(&self, u: f32, v_start: f32, v_step: f32, out: &mut [u32]) {
+        let x = u * self.width as f32 - 0.5;
+        let x0 = x.floor();
+        let fx = x - x0;
+        let left = self.column(Texture::wrap(x0 as i64, self.width));
+        let right = self.column(Texture::wrap(x0 as i64 + 1, self.width));
+
+        for (i, texel) in out.iter_mut().enumerate() {
+            let y = (v_start + i as f32 * v_step) * self.height as f32 - 0.5;
+            let y0 = y.floor();
+            let fy = y - y0;
+            let top = Texture::wrap(y0 as i64, self.height) as usize;
+            let bottom = Texture::wrap(y0 as i64 + 1, self.height) as usize;
+            *texel = weighted_average(&[
+                (left[top], (1.0 - fx) * (1.0 - fy)),
+                (left[bottom], (1.0 - fx) * fy),
+                (right[top], fx * (1.0 - fy)),
+                (right[bottom], fx * fy),
+            ]);
+        }
+    }
+}
+
+// Promedio de colores ARGB con los pesos dados (que suman 1). El color se pesa también
+// por el alfa, así los texels transparentes no tiñen a sus vecinos.
+fn weighted_average(texels: &[(u32, f32)]) -> u32 {
+    let (mut alpha, mut r, mut g, mut b) = (0.0, 0.0, 0.0, 0.0);
+    for &(color, weight) in texels {
+        let a = (color >> 24) as f32 * weight;
+        alpha += a;
+        r += ((color >> 16) & 0xFF) as f32 * a;
+        g += ((color >> 8) & 0xFF) as f32 * a;
+        b += (color & 0xFF) as f32 * a;
+    }
+    if alpha <= 0.0 {
+        return 0;
+    }
+    let channel = |sum: f32| ((sum / alpha).round() as u32).min(255);
+    ((alpha.round() as u32).min(255) << 24) | (channel(r) << 16) | (channel(g) << 8) | channel(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Colores opacos distintos en cada texel
+    fn pattern(width: u32, height: u32) -> Texture {
+        Texture::from_fn(width, height, |x, y| 0xFF000000 | (x * 0x010305 + y * 0x0A0701) & 0xFFFFFF)
+    }
+
+    // Parámetros de columna (u, v_start, v_step): con y sin repetición, hacia abajo y hacia arriba
+    const COLUMNS: [(f32, f32, f32); 6] = [
+        (0.0, 0.0, 1.0 / 600.0),
+        (0.3, 0.1, 0.0013),
+        (0.99, 0.75, 0.01),
+        (1.6, 2.3, 0.0071),
+        (-0.2, -0.4, 0.0047),
+        (0.5, 0.25, 0.0),
+    ];
+
+    // Texel que lee un muestreo punto a punto en (u, v), con las coordenadas repetidas
+    fn nearest_reference(texture: &Texture, u: f32, v: f32) -> u32 {
+        let x = (u * texture.width as f32).floor().rem_euclid(texture.width as f32) as u32;
+        let y = (v * texture.height as f32).floor().rem_euclid(texture.height as f32) as u32;
+        texture.get_pixel_argb(x, y)
+    }
+
+    fn bilinear_reference(texture: &Texture, u: f32, v: f32) -> u32 {
+        let x = u * texture.width as f32 - 0.5;
+        let y = v * texture.height as f32 - 0.5;
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+        let texel = |dx: f32, dy: f32| {
+            let tx = (x.floor() + dx).rem_euclid(texture.width as f32) as u32;
+            let ty = (y.floor() + dy).rem_euclid(texture.height as f32) as u32;
+            texture.get_pixel_argb(tx, ty)
+        };
+        weighted_average(&[
+            (texel(0.0, 0.0), (1.0 - fx) * (1.0 - fy)),
+            (texel(0.0, 1.0), (1.0 - fx) * fy),
+            (texel(1.0, 0.0), fx * (1.0 - fy)),
+            (texel(1.0, 1.0), fx * fy),
+        ])
+    }
+
+    // El punto fijo puede redondear distinto justo en el borde entre dos texels
+    fn near_texel_edge(coordinate: f32) -> bool {
+        let fraction = coordinate - coordinate.floor();
+        !(0.01..=0.99).contains(&fraction)
+    }
+
+    #[test]
+    fn nearest_column_matches_per_texel_sampling() {
+        for (width, height) in [(16, 64), (7, 50), (5, 1), (3, 97)] {
+            let texture = pattern(width, height);
+            for (u, v_start, v_step) in COLUMNS {
+                let mut out = vec![0; 700];
+                texture.sample_column(u, v_start, v_step, TextureFilter::Nearest, &mut out);
+                for (i, &texel) in out.iter().enumerate() {
+                    let v = v_start + i as f32 * v_step;
+                    if near_texel_edge(v * height as f32) {
+                        continue;
+                    }
+                    assert_eq!(
+                        texel,
+                        nearest_reference(&texture, u, v),
+                        "{}x{}, u {}, v {} (píxel {})",
+                        width, height, u, v, i
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bilinear_column_matches_per_texel_sampling() {
+        for (width, height) in [(16, 64), (7, 50)] {
+            let texture = pattern(width, height);
+            for (u, v_start, v_step) in COLUMNS {
+                let mut out = vec![0; 300];
+                texture.sample_column(u, v_start, v_step, TextureFilter::Bilinear, &mut out);
+                for (i, &texel) in out.iter().enumerate() {
+                    let v = v_start + i as f32 * v_step;
+                    assert_eq!(texel, bilinear_reference(&texture, u, v), "{}x{}, u {}, v {}", width, height, u, v);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sample_reads_one_texel() {
+        let texture = pattern(8, 6);
+        assert_eq!(texture.sample(0.3, 0.6, TextureFilter::Nearest), texture.get_pixel_argb(2, 3));
+        assert_eq!(texture.sample(1.3, -0.4, TextureFilter::Nearest), texture.get_pixel_argb(2, 3));
+    }
+
+    fn mip_height(texture: &Texture, screen_height: f32) -> u32 {
+        texture.mip_for_height(screen_height).height
+    }
+
+    #[test]
+    fn full_size_is_used_until_two_texels_per_pixel() {
+        let texture = pattern(64, 64);
+        assert!(std::ptr::eq(texture.mip_for_height(64.0), &texture));
+        assert!(std::ptr::eq(texture.mip_for_height(500.0), &texture));
+        assert_eq!(mip_height(&texture, 33.0), 64);
+        // Justo la mitad: ya alcanza el primer nivel reducido
+        assert_eq!(mip_height(&texture, 32.0), 32);
+        assert_eq!(mip_height(&texture, 31.0), 32);
+        assert_eq!(mip_height(&texture, 16.0), 16);
+    }
+
+    #[test]
+    fn tiny_walls_use_the_smallest_level() {
+        let texture = pattern(64, 64);
+        for screen_height in [1.0, 0.5, 0.0, -3.0] {
+            let mip = texture.mip_for_height(screen_height);
+            assert_eq!((mip.width, mip.height), (1, 1), "alto {}", screen_height);
+        }
+        // Una textura de 1x1 no tiene niveles reducidos
+        let single = pattern(1, 1);
+        assert!(single.mips.is_empty());
+        assert!(std::ptr::eq(single.mip_for_height(0.1), &single));
+    }
+
+    #[test]
+    fn mips_halve_non_power_of_two_sizes_down_to_one() {
+        let texture = pattern(100, 37);
+        let sizes: Vec<(u32, u32)> = texture.mips.iter().map(|mip| (mip.width, mip.height)).collect();
+        assert_eq!(sizes, [(50, 18), (25, 9), (12, 4), (6, 2), (3, 1), (1, 1)]);
+        for mip in &texture.mips {
+            assert_eq!(mip.pixels.len(), (mip.width * mip.height) as usize);
+        }
+
+        let tall = pattern(3, 40);
+        let sizes: Vec<(u32, u32)> = tall.mips.iter().map(|mip| (mip.width, mip.height)).collect();
+        assert_eq!(sizes, [(1, 20), (1, 10), (1, 5), (1, 2), (1, 1)]);
+        assert_eq!(mip_height(&tall, 10.0), 10);
+    }
+
+    #[test]
+    fn mips_average_by_alpha() {
+        // Mitad rojo opaco, mitad transparente: el promedio sigue siendo rojo, medio transparente
+        let texture = Texture::from_fn(2, 2, |x, _| if x == 0 { 0xFFFF0000 } else { 0x000000FF });
+        let mip = &texture.mips[0];
+        assert_eq!(mip.get_pixel_argb(0, 0), 0x80FF0000);
+    }
+}
+
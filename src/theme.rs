@@ -0,0 +1,180 @@
+// theme.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use crate::error::GameError;
+use crate::texture::Texture;
+
+// Tema que se carga para todos los niveles antes de sus propias entradas
+pub const DEFAULT_THEME: &str = "assets/theme.txt";
+
+// Color en el minimapa de los caracteres que no están en el tema
+const UNKNOWN_COLOR: u32 = 0xFF00FF;
+
+// Cómo se dibuja y se comporta un carácter del laberinto
+pub struct GlyphStyle {
+    pub frames: Vec<Arc<Texture>>, // un solo cuadro si la pared no está animada
+    pub frame_time: f32,           // segundos por cuadro de la animación
+    pub minimap_color: u32,
+    pub solid: bool,               // si bloquea el paso (los rayos se detienen igual)
+}
+
+impl GlyphStyle {
+    // Cuadro de la animación que corresponde al tiempo `time` en segundos
+    pub fn texture_at(&self, time: f32) -> &Texture {
+        if self.frames.len() == 1 || self.frame_time <= 0.0 {
+            return &self.frames[0];
+        }
+        let frame = (time / self.frame_time) as usize % self.frames.len();
+        &self.frames[frame]
+    }
+}
+
+// Registro carácter -> textura, color de minimapa, solidez y animación
+pub struct Theme {
+    glyphs: HashMap<char, GlyphStyle>,
+    surfaces: HashMap<char, Arc<Texture>>, // texturas de piso y techo (#floor, #ceiling)
+    textures: HashMap<String, Arc<Texture>>, // texturas ya cargadas, por ruta
+    missing: GlyphStyle,
+}
+
+impl Theme {
+    pub fn empty() -> Self {
+        Theme {
+            glyphs: HashMap::new(),
+            surfaces: HashMap::new(),
+            textures: HashMap::new(),
+            missing: GlyphStyle {
+                frames: vec![Arc::new(Texture::missing())],
+                frame_time: 0.0,
+                minimap_color: UNKNOWN_COLOR,
+                solid: true,
+            },
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, GameError> {
+        let mut theme = Theme::empty();
+        theme.extend_from_file(path)?;
+        Ok(theme)
+    }
+
+    // Agrega (o reemplaza) las entradas del archivo `path`
+    pub fn extend_from_file(&mut self, path: &str) -> Result<(), GameError> {
+        let text = fs::read_to_string(path).map_err(|source| GameError::Io { path: path.to_string(), source })?;
+        for line in text.lines() {
+            self.parse_line(path, line)?;
+        }
+        Ok(())
+    }
+
+    // Interpreta una línea `<carácter> = <textura> <color> solid|open [anim <segundos> <textura>...]`
+    // o `surface <carácter> = <textura>`. `path` solo se usa para los mensajes de error.
+    pub fn parse_line(&mut self, path: &str, line: &str) -> Result<(), GameError> {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+
+        let invalid = || GameError::InvalidLevel {
+            path: path.to_string(),
+            message: format!("tema: linea invalida '{}'", line),
+        };
+        // Una línea de pared (`s = ...`) nunca empieza con "surface ", así que no hay ambigüedad
+        if let Some(rest) = line.strip_prefix("surface ") {
+            let mut chars = rest.trim_start().chars();
+            let glyph = chars.next().ok_or_else(invalid)?;
+            let texture = chars.as_str().trim_start().strip_prefix('=').ok_or_else(invalid)?.trim();
+            if texture.is_empty() || texture.contains(char::is_whitespace) {
+                return Err(invalid());
+            }
+            let texture = self.texture(texture)?;
+            self.surfaces.insert(glyph, texture);
+            return Ok(());
+        }
+        // El carácter puede ser cualquiera (incluso '=' o '#'), así que se toma por posición
+        let mut chars = line.chars();
+        let glyph = chars.next().ok_or_else(invalid)?;
+        let rest = chars.as_str().trim_start().strip_prefix('=').ok_or_else(invalid)?;
+
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        let [texture, color, solid, animation @ ..] = &fields[..] else { return Err(invalid()) };
+        let minimap_color = u32::from_str_radix(color.trim_start_matches("0x"), 16).map_err(|_| invalid())?;
+        let solid = match *solid {
+            "solid" => true,
+            "open" => false,
+            _ => return Err(invalid()),
+        };
+
+        let mut frames = vec![self.texture(texture)?];
+        let frame_time = match animation {
+            [] => 0.0,
+            ["anim", seconds, extra @ ..] if !extra.is_empty() => {
+                for texture in extra {
+                    frames.push(self.texture(texture)?);
+                }
+                seconds.parse().map_err(|_| invalid())?
+            }
+            _ => return Err(invalid()),
+        };
+
+        self.glyphs.insert(glyph, GlyphStyle { frames, frame_time, minimap_color, solid });
+        Ok(())
+    }
+
+    // Cambia solo la textura de `glyph`; si no estaba en el tema se agrega como pared sólida
+    pub fn set_texture(&mut self, glyph: char, path: &str) -> Result<(), GameError> {
+        let texture = self.texture(path)?;
+        match self.glyphs.get_mut(&glyph) {
+            Some(style) => {
+                style.frames = vec![texture];
+                style.frame_time = 0.0;
+            }
+            None => {
+                self.glyphs.insert(glyph, GlyphStyle {
+                    frames: vec![texture],
+                    frame_time: 0.0,
+                    minimap_color: UNKNOWN_COLOR,
+                    solid: true,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn texture(&mut self, path: &str) -> Result<Arc<Texture>, GameError> {
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(Arc::clone(texture));
+        }
+        let texture = Arc::new(Texture::load(path)?);
+        self.textures.insert(path.to_string(), Arc::clone(&texture));
+        Ok(texture)
+    }
+
+    pub fn contains(&self, glyph: char) -> bool {
+        self.glyphs.contains_key(&glyph)
+    }
+
+    // Estilo del carácter; los desconocidos usan la textura de error
+    pub fn style(&self, glyph: char) -> &GlyphStyle {
+        self.glyphs.get(&glyph).unwrap_or(&self.missing)
+    }
+
+    pub fn is_solid(&self, glyph: char) -> bool {
+        self.style(glyph).solid
+    }
+
+    // Textura de piso/techo del carácter; None para el color plano por defecto
+    pub fn surface(&self, glyph: char) -> Option<&Texture> {
+        self.surfaces.get(&glyph).map(|texture| texture.as_ref())
+    }
+}
+
+// Sin entradas: no lee archivos. El tema por defecto se carga con `Theme::load(DEFAULT_THEME)`.
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::empty()
+    }
+}
@@ -0,0 +1,87 @@
+// Partidas completas sin ventana: la entrada sale de un guion
+
+use graficas_proy1::game::{Game, GameStatus};
+use graficas_proy1::input::{InputEvent, ScriptedInput, TimedInput};
+use graficas_proy1::maze::Level;
+use minifb::Key;
+use nalgebra_glm::Vec2;
+
+const DT: f32 = 1.0 / 60.0;
+
+fn open_room() -> Level {
+    let rows = ["+-------+", "|       |", "|       |", "|       |", "|       |", "+-------+"];
+    let mut level = Level::new("prueba", rows.iter().map(|row| row.chars().collect()).collect());
+    level.facing = 0.0;
+    level
+}
+
+// Juega `ticks` ticks con el guion y devuelve la partida
+fn play(script: Vec<TimedInput>, ticks: usize) -> Game {
+    let mut game = Game::new(open_room());
+    let mut input = ScriptedInput::new(script);
+    for _ in 0..ticks {
+        input.advance(DT);
+        game.step(&input, DT);
+    }
+    game
+}
+
+fn walk_forward() -> Vec<TimedInput> {
+    vec![
+        TimedInput { time: 0.0, event: InputEvent::KeyDown(Key::W) },
+        TimedInput { time: 0.5, event: InputEvent::KeyUp(Key::W) },
+        TimedInput { time: 0.6, event: InputEvent::MouseMove(40.0, 0.0) },
+    ]
+}
+
+#[test]
+fn holding_w_moves_the_player_forward() {
+    let start = Game::new(open_room()).player.pos;
+    let game = play(walk_forward(), 60);
+    assert!(game.player.pos.x > start.x + 20.0, "{:?} -> {:?}", start, game.player.pos);
+    assert_eq!(game.status(), GameStatus::Playing);
+}
+
+#[test]
+fn the_same_script_gives_the_same_game() {
+    let a = play(walk_forward(), 90);
+    let b = play(walk_forward(), 90);
+    assert_eq!(a.player.pos, b.player.pos);
+    assert_eq!(a.player.a, b.player.a);
+    assert_eq!(a.elapsed_time, b.elapsed_time);
+}
+
+#[test]
+fn the_clock_runs_out() {
+    let mut level = open_room();
+    level.time_limit = 1.0;
+    let mut game = Game::new(level);
+    let input = ScriptedInput::new(Vec::new());
+    for _ in 0..61 {
+        game.step(&input, DT);
+    }
+    assert_eq!(game.status(), GameStatus::TimeUp);
+}
+
+#[test]
+fn a_door_does_not_close_on_an_enemy() {
+    let rows = ["+-------+", "|       |", "|  D   e|", "|       |", "+-------+"];
+    let mut level = Level::new("puerta", rows.iter().map(|row| row.chars().collect()).collect());
+    level.facing = 0.0;
+    let mut game = Game::new(level);
+    game.doors.toggle(3, 2);
+    game.doors.update(10.0);
+    game.enemies[0].pos = game.level.spawn_position(70) + Vec2::new(70.0, 0.0);
+
+    let mut input = ScriptedInput::new(vec![TimedInput { time: 0.0, event: InputEvent::KeyDown(Key::E) }]);
+    input.advance(DT);
+    game.step(&input, DT);
+    assert!(game.doors.is_passable(3, 2));
+
+    // Sin el enemigo en la celda la puerta sí se cierra
+    game.enemies[0].pos = game.level.spawn_position(70) + Vec2::new(280.0, 0.0);
+    let mut input = ScriptedInput::new(vec![TimedInput { time: 0.0, event: InputEvent::KeyDown(Key::E) }]);
+    input.advance(DT);
+    game.step(&input, DT);
+    assert!(!game.doors.is_passable(3, 2));
+}
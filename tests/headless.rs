@@ -0,0 +1,60 @@
+// Cuadros sin ventana a partir de niveles armados en memoria
+
+use graficas_proy1::error::GameError;
+use graficas_proy1::headless::render_frame;
+use graficas_proy1::maze::generate::{generate, Algorithm, SPAWN_BLOCK};
+use graficas_proy1::maze::Level;
+use graficas_proy1::player::BLOCK_SIZE;
+use graficas_proy1::theme::{Theme, DEFAULT_THEME};
+use nalgebra_glm::Vec2;
+
+fn with_theme(mut level: Level) -> Level {
+    level.theme = Theme::load(DEFAULT_THEME).unwrap();
+    level
+}
+
+fn random_level(seed: u64) -> Level {
+    let mut level = with_theme(Level::new("aleatorio", generate(6, 6, Algorithm::Prim, seed)));
+    level.spawn = SPAWN_BLOCK;
+    level
+}
+
+fn spawn_position(level: &Level) -> Vec2 {
+    level.spawn_position(BLOCK_SIZE as usize)
+}
+
+#[test]
+fn empty_level_is_an_error_not_a_panic() {
+    let level = with_theme(Level::new("vacio", Vec::new()));
+    let result = render_frame(&level, Vec2::new(0.0, 0.0), 0.0, 64, 48);
+    assert!(matches!(result, Err(GameError::InvalidLevel { .. })));
+}
+
+#[test]
+fn generated_level_renders_the_same_frame_every_time() {
+    let level = random_level(7);
+    let pos = spawn_position(&level);
+    let first = render_frame(&level, pos, 0.3, 160, 120).unwrap();
+    let second = render_frame(&random_level(7), pos, 0.3, 160, 120).unwrap();
+    assert_eq!(first.buffer, second.buffer);
+    assert!(first.buffer.iter().any(|&color| color != first.buffer[0]));
+}
+
+#[test]
+fn tiny_frames_and_tall_mazes_do_not_panic() {
+    let level = random_level(3);
+    let pos = spawn_position(&level);
+    for (width, height) in [(1, 1), (2, 300), (300, 2)] {
+        render_frame(&level, pos, 1.0, width, height).unwrap();
+    }
+
+    // Más filas que píxeles tiene el minimapa
+    let mut rows = vec!["+-+".to_string()];
+    rows.extend((0..150).map(|_| "| |".to_string()));
+    rows.push("|g|".to_string());
+    rows.push("+-+".to_string());
+    let mut tall = with_theme(Level::new("alto", rows.iter().map(|row| row.chars().collect()).collect()));
+    tall.spawn = (1, 1);
+    let pos = spawn_position(&tall);
+    render_frame(&tall, pos, 1.5, 320, 240).unwrap();
+}